@@ -0,0 +1,44 @@
+//! Mediciones de rendimiento de la salida
+//!
+//! Solo se compila con la feature `bench`
+//! (`cargo build --features bench`). Mide con el PIT el costo promedio de
+//! las rutinas de pantalla más usadas, para comparar optimizaciones en
+//! QEMU o en hardware real con un número repetible.
+
+use crate::io::stdout::{self, colors::LIGHT_GRAY};
+use crate::{log, timer};
+
+/// Iteraciones de cada medición
+const ITERATIONS: u64 = 100;
+
+/// Ejecuta `f` `ITERATIONS` veces
+///
+/// # Retorna
+/// El costo promedio por iteración en microsegundos. La resolución de
+/// `timer::ticks` es de 1 ms, así que el total se mide una sola vez.
+fn measure(mut f: impl FnMut()) -> u64 {
+    let start = timer::ticks();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed_ms = timer::ticks() - start;
+
+    elapsed_ms * 1000 / ITERATIONS
+}
+
+/// Mide `clear_screen` y `scroll_up` y registra los resultados
+///
+/// Ensucia la pantalla; los resultados se escriben con `log::info!` al
+/// terminar, así que también salen por serie.
+pub fn run() {
+    let clear_us = measure(|| stdout::clear_screen(LIGHT_GRAY));
+    let scroll_us = measure(|| stdout::scroll_up(1, LIGHT_GRAY));
+
+    stdout::clear_screen(LIGHT_GRAY);
+    log::info!(
+        "bench: clear_screen {} us, scroll_up {} us ({} iterations)",
+        clear_us,
+        scroll_us,
+        ITERATIONS
+    );
+}
@@ -0,0 +1,74 @@
+//! Reloj en la esquina superior derecha
+//!
+//! Tarea cooperativa que muestra la hora del RTC como `HH:MM:SS` en la
+//! fila 0. Sirve también de ejemplo de una tarea que combina el RTC, el
+//! temporizador y la salida.
+
+use crate::io::stdin::overlay;
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{self, theme};
+use crate::{rtc, task, timer};
+use core::sync::atomic::{AtomicU64, Ordering};
+use heapless::String;
+
+/// Milisegundos entre dos lecturas del RTC
+const UPDATE_INTERVAL_MS: u64 = 1000;
+
+/// Tick (`timer::ticks`) de la última actualización
+static LAST_UPDATE: AtomicU64 = AtomicU64::new(0);
+
+/// Empieza a mostrar el reloj
+///
+/// Llamarla con el reloj ya en marcha no registra una segunda tarea.
+///
+/// # Retorna
+/// `false` si no hay lugar para otra tarea
+pub fn start() -> bool {
+    task::unregister(update);
+    if !task::register(update) {
+        return false;
+    }
+    draw();
+    true
+}
+
+/// Deja de actualizar el reloj; la última hora queda en pantalla
+pub fn stop() {
+    task::unregister(update);
+}
+
+/// Tarea: redibuja el reloj una vez por segundo
+///
+/// Se ejecuta en cada vuelta del loop, pero solo lee el RTC cuando pasó
+/// `UPDATE_INTERVAL_MS` desde la última lectura. Mientras el overlay del
+/// teclado está visible no se dibuja, porque ocupa la misma esquina.
+fn update() {
+    if overlay::is_visible() {
+        return;
+    }
+    if timer::ticks() - LAST_UPDATE.load(Ordering::Relaxed) >= UPDATE_INTERVAL_MS {
+        draw();
+    }
+}
+
+fn draw() {
+    use core::fmt::Write;
+
+    LAST_UPDATE.store(timer::ticks(), Ordering::Relaxed);
+    let time = rtc::read_time();
+
+    let mut text: String<8> = String::new();
+    let _ = write!(
+        text,
+        "{:02}:{:02}:{:02}",
+        time.hours, time.minutes, time.seconds
+    );
+
+    // Por segundo cambian uno o dos dígitos: el resto no se reescribe
+    let color = theme::theme().accent_color();
+    let x = stdout::width().saturating_sub(text.len());
+    for (offset, character) in text.bytes().enumerate() {
+        let syschar = SysPrintableChar::new(character, color, (x + offset) as u8, 0);
+        stdout::write_char_at_if_changed(syschar);
+    }
+}
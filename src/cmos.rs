@@ -0,0 +1,32 @@
+//! Acceso a la memoria CMOS
+//!
+//! La CMOS guarda la configuración del BIOS y el reloj de tiempo real.
+//! Se accede escribiendo el número de registro en el puerto 0x70 y
+//! leyendo su valor en el 0x71.
+
+use crate::io::ports::{read_port, write_port};
+use crate::sync::without_interrupts;
+
+/// Puerto de selección de registro
+const INDEX_PORT: u16 = 0x70;
+
+/// Puerto de datos del registro seleccionado
+const DATA_PORT: u16 = 0x71;
+
+/// Bit del puerto de índice que deshabilita el NMI
+const NMI_DISABLE: u8 = 0x80;
+
+/// Lee un registro de la CMOS
+///
+/// El NMI queda deshabilitado mientras se selecciona el registro, para
+/// que no llegue entre la escritura del índice y la lectura del dato y
+/// deje la CMOS en un estado inconsistente; después se vuelve a
+/// habilitar.
+pub fn read_register(register: u8) -> u8 {
+    without_interrupts(|| unsafe {
+        write_port(INDEX_PORT, NMI_DISABLE | register);
+        let value = read_port(DATA_PORT);
+        write_port(INDEX_PORT, register & !NMI_DISABLE);
+        value
+    })
+}
@@ -0,0 +1,173 @@
+//! Identificación del procesador mediante `cpuid`
+//!
+//! Expone el fabricante (hoja 0) y algunas capacidades comunes (hoja 1).
+//! Todos los procesadores x86_64 soportan `cpuid`, así que no hace falta
+//! comprobar el bit ID de RFLAGS.
+
+use crate::io::stdout::TextCursor;
+
+/// Registros retornados por `cpuid`
+#[derive(Debug, Clone, Copy)]
+pub struct CpuidResult {
+    pub eax: u32,
+    pub ebx: u32,
+    pub ecx: u32,
+    pub edx: u32,
+}
+
+/// Ejecuta `cpuid` para la hoja indicada (subhoja 0)
+#[inline]
+pub fn cpuid(leaf: u32) -> CpuidResult {
+    let eax: u32;
+    let ebx: u32;
+    let ecx: u32;
+    let edx: u32;
+    unsafe {
+        // LLVM reserva rbx, así que se guarda en un registro temporal
+        core::arch::asm!(
+            "mov {tmp:r}, rbx",
+            "cpuid",
+            "xchg {tmp:r}, rbx",
+            tmp = out(reg) ebx,
+            inout("eax") leaf => eax,
+            inout("ecx") 0u32 => ecx,
+            out("edx") edx,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+    CpuidResult { eax, ebx, ecx, edx }
+}
+
+/// Hoja más alta soportada por `cpuid`
+pub fn max_leaf() -> u32 {
+    cpuid(0).eax
+}
+
+/// Fabricante del procesador (p. ej. "GenuineIntel" o "AuthenticAMD")
+///
+/// La string se arma con EBX, EDX y ECX de la hoja 0, en ese orden.
+pub fn vendor() -> [u8; 12] {
+    let result = cpuid(0);
+    let mut vendor = [0u8; 12];
+    vendor[0..4].copy_from_slice(&result.ebx.to_le_bytes());
+    vendor[4..8].copy_from_slice(&result.edx.to_le_bytes());
+    vendor[8..12].copy_from_slice(&result.ecx.to_le_bytes());
+    vendor
+}
+
+/// Registro de la hoja 1 donde se reporta una capacidad
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FeatureRegister {
+    Ecx,
+    Edx,
+}
+
+/// Capacidades reportadas en la hoja 1 de `cpuid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Feature {
+    Fpu,
+    Tsc,
+    Msr,
+    Pae,
+    Apic,
+    Sse,
+    Sse2,
+    Sse3,
+    Sse41,
+    Sse42,
+    Popcnt,
+    Avx,
+    Hypervisor,
+}
+
+impl Feature {
+    /// Todas las capacidades conocidas, en el orden en que se muestran
+    pub const ALL: [Feature; 13] = [
+        Feature::Fpu,
+        Feature::Tsc,
+        Feature::Msr,
+        Feature::Pae,
+        Feature::Apic,
+        Feature::Sse,
+        Feature::Sse2,
+        Feature::Sse3,
+        Feature::Sse41,
+        Feature::Sse42,
+        Feature::Popcnt,
+        Feature::Avx,
+        Feature::Hypervisor,
+    ];
+
+    /// Registro y bit donde se reporta la capacidad
+    const fn location(self) -> (FeatureRegister, u32) {
+        match self {
+            Feature::Fpu => (FeatureRegister::Edx, 0),
+            Feature::Tsc => (FeatureRegister::Edx, 4),
+            Feature::Msr => (FeatureRegister::Edx, 5),
+            Feature::Pae => (FeatureRegister::Edx, 6),
+            Feature::Apic => (FeatureRegister::Edx, 9),
+            Feature::Sse => (FeatureRegister::Edx, 25),
+            Feature::Sse2 => (FeatureRegister::Edx, 26),
+            Feature::Sse3 => (FeatureRegister::Ecx, 0),
+            Feature::Sse41 => (FeatureRegister::Ecx, 19),
+            Feature::Sse42 => (FeatureRegister::Ecx, 20),
+            Feature::Popcnt => (FeatureRegister::Ecx, 23),
+            Feature::Avx => (FeatureRegister::Ecx, 28),
+            Feature::Hypervisor => (FeatureRegister::Ecx, 31),
+        }
+    }
+
+    /// Nombre corto de la capacidad
+    pub const fn name(self) -> &'static str {
+        match self {
+            Feature::Fpu => "fpu",
+            Feature::Tsc => "tsc",
+            Feature::Msr => "msr",
+            Feature::Pae => "pae",
+            Feature::Apic => "apic",
+            Feature::Sse => "sse",
+            Feature::Sse2 => "sse2",
+            Feature::Sse3 => "sse3",
+            Feature::Sse41 => "sse4.1",
+            Feature::Sse42 => "sse4.2",
+            Feature::Popcnt => "popcnt",
+            Feature::Avx => "avx",
+            Feature::Hypervisor => "hypervisor",
+        }
+    }
+}
+
+/// Verifica si el procesador reporta una capacidad
+pub fn has_feature(feature: Feature) -> bool {
+    if max_leaf() < 1 {
+        return false;
+    }
+
+    let result = cpuid(1);
+    let (register, bit) = feature.location();
+    let value = match register {
+        FeatureRegister::Ecx => result.ecx,
+        FeatureRegister::Edx => result.edx,
+    };
+    value & (1 << bit) != 0
+}
+
+/// Escribe el fabricante y las capacidades detectadas
+///
+/// # Argumentos
+/// * `cursor` - Cursor donde se escribe la información
+pub fn print_cpu_info(cursor: &mut TextCursor) {
+    cursor.write_string(b"CPU: ");
+    cursor.write_string(&vendor());
+    cursor.write_string(b"\nFeatures:");
+
+    // Cada capacidad empieza en una parada de tabulación, así que quedan
+    // alineadas en columnas
+    for feature in Feature::ALL {
+        if has_feature(feature) {
+            cursor.tab();
+            cursor.write_string(feature.name().as_bytes());
+        }
+    }
+    cursor.write_string(b"\n");
+}
@@ -0,0 +1,329 @@
+//! Demo de snake para probar la entrada y el temporizador
+//!
+//! Se activa con F2 y no corre por defecto. Combina varias piezas del
+//! kernel: polling no bloqueante del teclado (flechas y Escape), pasos
+//! temporizados con el PIT, redibujado completo con el back buffer y
+//! colisiones con los bordes de la pantalla. Al salir se restaura la
+//! pantalla que había antes.
+
+use crate::io::stdin::keyboard::{self, Key, QueueOverflowPolicy};
+use crate::io::stdin::overlay;
+use crate::io::stdout::colors::{BLACK, LIGHT_GRAY, LIGHT_GREEN, LIGHT_RED, YELLOW, make_color};
+use crate::io::stdout::cp437::box_drawing;
+use crate::io::stdout::structs::{self, SysPrintableChar};
+use crate::io::stdout::{self, Screen, VGA_MAX_CELLS};
+use crate::timer;
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+use heapless::Deque;
+
+/// Milisegundos entre cada paso de la serpiente
+const STEP_MS: u64 = 120;
+
+/// Largo máximo de la serpiente
+const MAX_LENGTH: usize = 256;
+
+/// Fila donde empieza el borde del tablero (la fila 0 es el marcador)
+const BOARD_TOP: u8 = 1;
+
+/// Carácter de bloque completo en CP437
+const FULL_BLOCK: u8 = 0xDB;
+
+/// Mensaje al terminar la partida
+const GAME_OVER: &[u8] = b" GAME OVER - press any key ";
+
+/// Evita que F2 dentro del demo lo vuelva a iniciar
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Dirección de movimiento de la serpiente
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    /// Calcula la celda vecina en esta dirección
+    fn step(self, (x, y): (u8, u8)) -> (u8, u8) {
+        match self {
+            Direction::Up => (x, y.wrapping_sub(1)),
+            Direction::Down => (x, y.wrapping_add(1)),
+            Direction::Left => (x.wrapping_sub(1), y),
+            Direction::Right => (x.wrapping_add(1), y),
+        }
+    }
+}
+
+/// Generador pseudoaleatorio xorshift para ubicar la comida
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Número en el rango `[low, high)`
+    fn range(&mut self, low: u8, high: u8) -> u8 {
+        low + (self.next() % (high - low) as u64) as u8
+    }
+}
+
+/// Estado de una partida
+struct Game {
+    snake: Deque<(u8, u8), MAX_LENGTH>,
+    direction: Direction,
+    pending: Direction,
+    food: (u8, u8),
+    score: u32,
+    width: u8,
+    height: u8,
+    rng: Rng,
+}
+
+impl Game {
+    fn new() -> Self {
+        let width = stdout::width() as u8;
+        let height = stdout::height() as u8;
+
+        let mut game = Self {
+            snake: Deque::new(),
+            direction: Direction::Right,
+            pending: Direction::Right,
+            food: (0, 0),
+            score: 0,
+            width,
+            height,
+            rng: Rng::new(timer::ticks()),
+        };
+
+        let _ = game.snake.push_back((width / 2, height / 2));
+        game.food = game.random_free_cell();
+        game
+    }
+
+    /// Verifica si la celda está dentro del borde del tablero
+    fn is_inside(&self, (x, y): (u8, u8)) -> bool {
+        x > 0 && x < self.width - 1 && y > BOARD_TOP && y < self.height - 1
+    }
+
+    fn random_free_cell(&mut self) -> (u8, u8) {
+        loop {
+            let cell = (
+                self.rng.range(1, self.width - 1),
+                self.rng.range(BOARD_TOP + 1, self.height - 1),
+            );
+            if !self.snake.iter().any(|&part| part == cell) {
+                return cell;
+            }
+        }
+    }
+
+    /// Cambia la dirección para el próximo paso (sin permitir reversa)
+    fn turn(&mut self, direction: Direction) {
+        if direction != self.direction.opposite() {
+            self.pending = direction;
+        }
+    }
+
+    /// Avanza un paso
+    ///
+    /// # Retorna
+    /// `false` si la serpiente chocó con el borde o consigo misma
+    fn step(&mut self) -> bool {
+        self.direction = self.pending;
+
+        let head = *self
+            .snake
+            .back()
+            .unwrap_or(&(self.width / 2, self.height / 2));
+        let new_head = self.direction.step(head);
+
+        if !self.is_inside(new_head) || self.snake.iter().any(|&part| part == new_head) {
+            return false;
+        }
+
+        if self.snake.is_full() {
+            self.snake.pop_front();
+        }
+        let _ = self.snake.push_back(new_head);
+
+        if new_head == self.food {
+            self.score += 1;
+            self.food = self.random_free_cell();
+        } else {
+            self.snake.pop_front();
+        }
+
+        true
+    }
+
+    /// Dibuja el cuadro completo en el back buffer
+    fn render(&self, screen: &mut Screen) {
+        let border_color = make_color(LIGHT_GRAY, BLACK);
+        screen.clear(border_color);
+
+        let mut score_line: heapless::String<32> = heapless::String::new();
+        let _ = write!(score_line, "SNAKE  score: {}  (Esc exits)", self.score);
+        screen.write_string(score_line.as_bytes(), YELLOW, 1, 0);
+
+        let right = self.width - 1;
+        let bottom = self.height - 1;
+        for x in 1..right {
+            screen.write_char(SysPrintableChar::new(
+                box_drawing::HORIZONTAL,
+                border_color,
+                x,
+                BOARD_TOP,
+            ));
+            screen.write_char(SysPrintableChar::new(
+                box_drawing::HORIZONTAL,
+                border_color,
+                x,
+                bottom,
+            ));
+        }
+        for y in BOARD_TOP + 1..bottom {
+            screen.write_char(SysPrintableChar::new(
+                box_drawing::VERTICAL,
+                border_color,
+                0,
+                y,
+            ));
+            screen.write_char(SysPrintableChar::new(
+                box_drawing::VERTICAL,
+                border_color,
+                right,
+                y,
+            ));
+        }
+        screen.write_char(SysPrintableChar::new(
+            box_drawing::TOP_LEFT,
+            border_color,
+            0,
+            BOARD_TOP,
+        ));
+        screen.write_char(SysPrintableChar::new(
+            box_drawing::TOP_RIGHT,
+            border_color,
+            right,
+            BOARD_TOP,
+        ));
+        screen.write_char(SysPrintableChar::new(
+            box_drawing::BOTTOM_LEFT,
+            border_color,
+            0,
+            bottom,
+        ));
+        screen.write_char(SysPrintableChar::new(
+            box_drawing::BOTTOM_RIGHT,
+            border_color,
+            right,
+            bottom,
+        ));
+
+        let (food_x, food_y) = self.food;
+        screen.write_char(SysPrintableChar::new(b'*', LIGHT_RED, food_x, food_y));
+
+        for &(x, y) in self.snake.iter() {
+            screen.write_char(SysPrintableChar::new(FULL_BLOCK, LIGHT_GREEN, x, y));
+        }
+    }
+}
+
+/// Ejecuta el demo hasta que se presione Escape o termine la partida
+///
+/// Pensado para registrarse como handler de F2. Mientras corre consume
+/// toda la entrada del teclado; al terminar restaura la pantalla.
+pub fn run() {
+    if RUNNING.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let mut saved = [0u16; VGA_MAX_CELLS];
+    stdout::save_screen(&mut saved);
+
+    // En el juego importan las últimas teclas; al volver, el shell
+    // necesita las primeras para no desordenar lo escrito
+    keyboard::set_overflow_policy(QueueOverflowPolicy::DropOldest);
+    play();
+    keyboard::set_overflow_policy(QueueOverflowPolicy::DropNewest);
+
+    stdout::restore_screen(&saved);
+    RUNNING.store(false, Ordering::Relaxed);
+}
+
+/// Loop principal de una partida
+fn play() {
+    let mut screen = Screen::new();
+    let mut game = Game::new();
+    let mut next_step = timer::ticks() + STEP_MS;
+
+    game.render(&mut screen);
+    screen.present();
+
+    loop {
+        // Entrada no bloqueante: se procesan todas las teclas pendientes
+        let overlay_was_visible = overlay::is_visible();
+        while let Some(event) = keyboard::poll_keyboard_event() {
+            match event.key {
+                Key::Up => game.turn(Direction::Up),
+                Key::Down => game.turn(Direction::Down),
+                Key::Left => game.turn(Direction::Left),
+                Key::Right => game.turn(Direction::Right),
+                Key::Escape => return,
+                _ => {}
+            }
+        }
+
+        // Al cerrarse, el overlay restaura lo que había cuando se abrió,
+        // que ya no coincide con lo que el back buffer cree mostrado
+        if overlay_was_visible && !overlay::is_visible() {
+            screen.mark_all_dirty();
+        }
+
+        if timer::ticks() < next_step {
+            core::hint::spin_loop();
+            continue;
+        }
+        next_step += STEP_MS;
+
+        if !game.step() {
+            break;
+        }
+
+        game.render(&mut screen);
+        screen.present();
+    }
+
+    // Fin de la partida: el mensaje se arma en el origen, se centra en el
+    // tablero y se espera una tecla antes de salir
+    let (message_width, _) = stdout::measure_string(GAME_OVER, 0);
+    let dx = (game.width.saturating_sub(message_width) / 2).min(i8::MAX as u8) as i8;
+    let dy = (game.height / 2).min(i8::MAX as u8) as i8;
+    let message = SysPrintableChar::new_string(GAME_OVER, LIGHT_RED, 0, 0);
+    for syschar in structs::translate(message.into_iter(), dx, dy) {
+        screen.write_char(syschar);
+    }
+    screen.present();
+    while keyboard::poll_keyboard_event().is_none() {
+        core::hint::spin_loop();
+    }
+}
@@ -0,0 +1,75 @@
+//! Loop de eventos
+//!
+//! Junta las fuentes de eventos del kernel (teclado y temporizador) en un
+//! solo `Event`, para que un programa interactivo atienda todo desde un
+//! único `match` en lugar de consultar cada subsistema por separado.
+
+use crate::io::stdin::keyboard::{self, KeyEvent};
+use crate::timer::Throttle;
+
+/// Evento entregado por `EventLoop`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    /// Se presionó una tecla
+    Key(KeyEvent),
+    /// Pasó un intervalo del loop
+    Tick,
+}
+
+/// Fuente unificada de eventos de teclado y temporizador
+///
+/// ```ignore
+/// let mut events = EventLoop::new(16);
+/// loop {
+///     match events.next_event() {
+///         Event::Key(key) => { /* atender la tecla */ }
+///         Event::Tick => { /* trabajo periódico */ }
+///     }
+/// }
+/// ```
+pub struct EventLoop {
+    /// Ritmo de los `Event::Tick`
+    throttle: Throttle,
+}
+
+impl EventLoop {
+    /// Crea un loop que emite `Event::Tick` cada `tick_interval_ms`
+    ///
+    /// El primer tick vence un intervalo después de la llamada.
+    pub fn new(tick_interval_ms: u64) -> Self {
+        Self {
+            throttle: Throttle::new(tick_interval_ms),
+        }
+    }
+
+    /// Obtiene el próximo evento sin bloquear
+    ///
+    /// Si hay un handler instalado con `keyboard::set_input_handler`, las
+    /// teclas pendientes se le entregan (`keyboard::service`) y no llegan
+    /// como `Event::Key`. Las teclas tienen prioridad sobre el tick, que
+    /// sigue el ritmo de `timer::Throttle::poll_frame`: si se atrasó más
+    /// de un intervalo, se emite uno solo.
+    pub fn poll_event(&mut self) -> Option<Event> {
+        keyboard::service();
+        if let Some(event) = keyboard::poll_keyboard_event() {
+            return Some(Event::Key(event));
+        }
+
+        self.throttle.poll_frame().then_some(Event::Tick)
+    }
+
+    /// Espera el próximo evento
+    ///
+    /// Mientras no hay eventos la CPU gira en un spin loop. Cuando el
+    /// teclado y el PIT funcionen por interrupciones, la espera podrá
+    /// hacerse con `system::wait_for_interrupt`; hoy no hay IDT y la
+    /// primera IRQ causaría un triple fault.
+    pub fn next_event(&mut self) -> Event {
+        loop {
+            if let Some(event) = self.poll_event() {
+                return event;
+            }
+            core::hint::spin_loop();
+        }
+    }
+}
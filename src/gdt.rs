@@ -0,0 +1,77 @@
+//! Tabla de descriptores globales (GDT)
+//!
+//! El kernel corre en modo largo de 64 bits (target `x86_64`), donde la
+//! segmentación es casi plana: la base y el límite de los segmentos de
+//! código y datos se ignoran, y solo importan los bits de tipo, nivel de
+//! privilegio y el bit L (64 bits) del segmento de código. La tabla tiene
+//! lo mínimo: el descriptor nulo, un segmento de código y uno de datos,
+//! ambos de ring 0. Todavía no hay TSS.
+
+/// Descriptor nulo (obligatorio en la primera entrada)
+const NULL_DESCRIPTOR: u64 = 0;
+
+/// Código de ring 0, 64 bits: presente, ejecutable/legible, L = 1
+///
+/// Los descriptores ya tienen el bit "accedido" en 1: la tabla está en
+/// memoria de solo lectura y, si el bit estuviera en 0, la CPU intentaría
+/// escribirlo al cargar el selector y eso terminaría en un fallo.
+const KERNEL_CODE_DESCRIPTOR: u64 = 0x00AF_9B00_0000_FFFF;
+
+/// Datos de ring 0: presente, escribible, accedido (base y límite se
+/// ignoran)
+const KERNEL_DATA_DESCRIPTOR: u64 = 0x00CF_9300_0000_FFFF;
+
+/// Selector del segmento de código del kernel (entrada 1, RPL 0)
+pub const KERNEL_CODE_SELECTOR: u16 = 0x08;
+
+/// Selector del segmento de datos del kernel (entrada 2, RPL 0)
+pub const KERNEL_DATA_SELECTOR: u16 = 0x10;
+
+static GDT: [u64; 3] = [
+    NULL_DESCRIPTOR,
+    KERNEL_CODE_DESCRIPTOR,
+    KERNEL_DATA_DESCRIPTOR,
+];
+
+/// Operando de `lgdt`: tamaño de la tabla menos uno y su dirección
+#[repr(C, packed)]
+struct GdtPointer {
+    limit: u16,
+    base: u64,
+}
+
+/// Carga la GDT del kernel y recarga los registros de segmento
+///
+/// CS se recarga con un `retfq` (no se puede mover directo), y DS, ES,
+/// SS, FS y GS con el selector de datos. Debe llamarse al principio de
+/// `_start`, antes de configurar la IDT, cuyas entradas usan
+/// `KERNEL_CODE_SELECTOR`.
+pub fn init() {
+    let pointer = GdtPointer {
+        limit: (core::mem::size_of_val(&GDT) - 1) as u16,
+        base: GDT.as_ptr() as u64,
+    };
+
+    unsafe {
+        core::arch::asm!(
+            "lgdt [{pointer}]",
+            // Recargar CS con un far return a la etiqueta siguiente
+            "push {code}",
+            "lea {tmp}, [rip + 2f]",
+            "push {tmp}",
+            "retfq",
+            "2:",
+            "mov ds, {data:x}",
+            "mov es, {data:x}",
+            "mov ss, {data:x}",
+            "mov fs, {data:x}",
+            "mov gs, {data:x}",
+            pointer = in(reg) &pointer,
+            code = in(reg) KERNEL_CODE_SELECTOR as u64,
+            data = in(reg) KERNEL_DATA_SELECTOR as u64,
+            // `out` y no `lateout`: se escribe antes de leer `data`
+            tmp = out(reg) _,
+            options(preserves_flags)
+        );
+    }
+}
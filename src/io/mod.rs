@@ -3,5 +3,9 @@
 //! Este módulo organiza todas las funcionalidades de entrada y salida
 //! del kernel, incluyendo entrada desde teclado y salida a pantalla.
 
+pub mod mouse;
+pub mod output;
+pub mod ports;
+pub mod serial;
 pub mod stdin;
 pub mod stdout;
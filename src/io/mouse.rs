@@ -0,0 +1,145 @@
+//! Controlador del mouse PS/2
+//!
+//! El mouse está conectado al segundo canal (auxiliar) del mismo
+//! controlador 8042 que el teclado. Sus bytes llegan por el mismo puerto
+//! de datos y se distinguen por el bit 5 del registro de estado; el
+//! módulo del teclado los separa al leer y los entrega a `handle_byte`.
+//! Como el kernel todavía trabaja por polling, los paquetes se arman a
+//! medida que se vacía el controlador y no desde IRQ12.
+
+use crate::io::stdin::keyboard::{self, KbError};
+use crate::sync::SpinLock;
+use heapless::Deque;
+
+/// Comandos del controlador relacionados con el puerto auxiliar
+mod commands {
+    pub const ENABLE_AUX: u8 = 0xA8; // Habilitar el segundo canal
+    pub const WRITE_AUX: u8 = 0xD4; // El próximo byte de datos va al mouse
+}
+
+/// Comandos del mouse
+mod mouse_commands {
+    pub const SET_DEFAULTS: u8 = 0xF6;
+    pub const ENABLE_STREAMING: u8 = 0xF4;
+}
+
+/// Respuesta del mouse que confirma un comando
+const ACK: u8 = 0xFA;
+
+/// Bit de configuración del controlador que habilita IRQ12
+const CONFIG_AUX_INTERRUPT: u8 = 0x02;
+
+/// Bit de configuración del controlador que apaga el reloj del mouse
+const CONFIG_AUX_CLOCK_DISABLED: u8 = 0x20;
+
+/// Bits del primer byte de cada paquete
+mod packet_bits {
+    pub const BUTTONS: u8 = 0x07; // Izquierdo, derecho, medio
+    pub const ALWAYS_ONE: u8 = 0x08; // Siempre en 1: sirve para alinear
+    pub const X_SIGN: u8 = 0x10;
+    pub const Y_SIGN: u8 = 0x20;
+    pub const X_OVERFLOW: u8 = 0x40;
+    pub const Y_OVERFLOW: u8 = 0x80;
+}
+
+/// Movimiento relativo y botones reportados por un paquete del mouse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseState {
+    /// Desplazamiento horizontal (positivo hacia la derecha)
+    pub dx: i8,
+    /// Desplazamiento vertical (positivo hacia arriba)
+    pub dy: i8,
+    /// Botones presionados: bit 0 izquierdo, bit 1 derecho, bit 2 medio
+    pub buttons: u8,
+}
+
+/// Estado del armado de paquetes de 3 bytes
+struct PacketDecoder {
+    bytes: [u8; 3],
+    index: usize,
+}
+
+static DECODER: SpinLock<PacketDecoder> = SpinLock::new(PacketDecoder {
+    bytes: [0; 3],
+    index: 0,
+});
+
+/// Capacidad de la cola de paquetes pendientes
+const PACKET_QUEUE_CAPACITY: usize = 16;
+
+static PACKETS: SpinLock<Deque<MouseState, PACKET_QUEUE_CAPACITY>> = SpinLock::new(Deque::new());
+
+/// Envía un comando al mouse y espera su ACK
+fn send_mouse_command(command: u8) -> Result<(), KbError> {
+    keyboard::send_command(commands::WRITE_AUX)?;
+    keyboard::send_data(command)?;
+
+    match keyboard::read_data()? {
+        ACK => Ok(()),
+        response => Err(KbError::UnexpectedResponse(response)),
+    }
+}
+
+/// Habilita el mouse y el envío continuo de paquetes
+pub fn init() -> Result<(), KbError> {
+    keyboard::send_command(commands::ENABLE_AUX)?;
+
+    let config = keyboard::read_config()?;
+    keyboard::write_config((config | CONFIG_AUX_INTERRUPT) & !CONFIG_AUX_CLOCK_DISABLED)?;
+
+    send_mouse_command(mouse_commands::SET_DEFAULTS)?;
+    send_mouse_command(mouse_commands::ENABLE_STREAMING)
+}
+
+/// Convierte un desplazamiento de 9 bits (signo en el primer byte) a `i8`
+fn movement(value: u8, negative: bool) -> i8 {
+    let full = if negative {
+        value as i16 - 0x100
+    } else {
+        value as i16
+    };
+    full.clamp(i8::MIN as i16, i8::MAX as i16) as i8
+}
+
+/// Procesa un byte recibido del mouse
+///
+/// Los bytes se acumulan hasta completar un paquete. Un primer byte sin
+/// el bit 3 en 1 indica que se perdió la alineación y se descarta hasta
+/// encontrar un inicio válido. Los paquetes con overflow se ignoran.
+pub fn handle_byte(byte: u8) {
+    let mut decoder = DECODER.lock();
+
+    if decoder.index == 0 && byte & packet_bits::ALWAYS_ONE == 0 {
+        return;
+    }
+
+    let index = decoder.index;
+    decoder.bytes[index] = byte;
+    decoder.index += 1;
+
+    if decoder.index < decoder.bytes.len() {
+        return;
+    }
+    decoder.index = 0;
+
+    let [flags, x, y] = decoder.bytes;
+    if flags & (packet_bits::X_OVERFLOW | packet_bits::Y_OVERFLOW) != 0 {
+        return;
+    }
+
+    let state = MouseState {
+        dx: movement(x, flags & packet_bits::X_SIGN != 0),
+        dy: movement(y, flags & packet_bits::Y_SIGN != 0),
+        buttons: flags & packet_bits::BUTTONS,
+    };
+    let _ = PACKETS.lock().push_back(state);
+}
+
+/// Obtiene el siguiente paquete del mouse (no bloqueante)
+///
+/// Vacía el controlador antes de consultar, así que las teclas que lleguen
+/// mientras tanto quedan en la cola del teclado.
+pub fn poll_mouse() -> Option<MouseState> {
+    keyboard::drain_controller();
+    PACKETS.lock().pop_front()
+}
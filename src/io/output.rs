@@ -0,0 +1,94 @@
+//! Salida de texto hacia todos los destinos activos
+//!
+//! `kprint!` y `kprintln!` escriben el mismo texto en cada destino
+//! habilitado (pantalla VGA y puerto serie), así que el mismo código sirve
+//! para corridas con pantalla y sin ella (solo serie). Los destinos se
+//! habilitan y deshabilitan en tiempo de ejecución.
+
+use crate::io::serial::SERIAL;
+use crate::sync::without_interrupts;
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Destino de la salida de texto
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputSink {
+    /// Writer global de la pantalla (`stdout::WRITER`)
+    Vga,
+    /// Puerto serie COM1 (`serial::SERIAL`); debe estar inicializado
+    Serial,
+}
+
+impl OutputSink {
+    /// Todos los destinos, en el orden en que se escriben
+    pub const ALL: [OutputSink; 2] = [OutputSink::Vga, OutputSink::Serial];
+
+    /// Bit del destino en `ENABLED_SINKS`
+    const fn mask(self) -> u8 {
+        match self {
+            OutputSink::Vga => 0x01,
+            OutputSink::Serial => 0x02,
+        }
+    }
+
+    /// Escribe texto formateado en el destino
+    ///
+    /// En la pantalla se escribe como `print!`, así que el texto también
+    /// queda en el scrollback.
+    fn write_fmt(self, args: fmt::Arguments) -> fmt::Result {
+        match self {
+            OutputSink::Vga => {
+                crate::print!("{}", args);
+                Ok(())
+            }
+            OutputSink::Serial => without_interrupts(|| SERIAL.lock().write_fmt(args)),
+        }
+    }
+}
+
+/// Destinos habilitados; por defecto solo la pantalla
+static ENABLED_SINKS: AtomicU8 = AtomicU8::new(OutputSink::Vga.mask());
+
+/// Habilita un destino
+pub fn enable(sink: OutputSink) {
+    ENABLED_SINKS.fetch_or(sink.mask(), Ordering::Relaxed);
+}
+
+/// Deshabilita un destino
+pub fn disable(sink: OutputSink) {
+    ENABLED_SINKS.fetch_and(!sink.mask(), Ordering::Relaxed);
+}
+
+/// Verifica si un destino está habilitado
+pub fn is_enabled(sink: OutputSink) -> bool {
+    ENABLED_SINKS.load(Ordering::Relaxed) & sink.mask() != 0
+}
+
+#[doc(hidden)]
+pub fn _kprint(args: fmt::Arguments) {
+    for sink in OutputSink::ALL {
+        if is_enabled(sink) {
+            let _ = sink.write_fmt(args);
+        }
+    }
+}
+
+/// Imprime texto formateado en todos los destinos habilitados
+#[macro_export]
+macro_rules! kprint {
+    ($($arg:tt)*) => {
+        $crate::io::output::_kprint(format_args!($($arg)*))
+    };
+}
+
+/// Imprime texto formateado en todos los destinos habilitados seguido de
+/// un salto de línea
+#[macro_export]
+macro_rules! kprintln {
+    () => {
+        $crate::kprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::kprint!("{}\n", format_args!($($arg)*))
+    };
+}
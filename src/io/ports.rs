@@ -0,0 +1,57 @@
+//! Acceso a los puertos de I/O de x86
+//!
+//! Envoltorios mínimos sobre las instrucciones `in`/`out` usados por los
+//! controladores de hardware (teclado, VGA, etc.).
+
+/// Lee un byte desde un puerto de I/O
+///
+/// # Seguridad
+/// Leer de un puerto puede tener efectos secundarios en el hardware; el
+/// llamador debe saber qué dispositivo responde en `port`.
+#[inline]
+pub unsafe fn read_port(port: u16) -> u8 {
+    let result: u8;
+    unsafe {
+        core::arch::asm!(
+            "in al, dx",
+            out("al") result,
+            in("dx") port,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+    result
+}
+
+/// Escribe un byte a un puerto de I/O
+///
+/// # Seguridad
+/// Escribir en un puerto puede reconfigurar el hardware; el llamador debe
+/// saber qué dispositivo responde en `port`.
+#[inline]
+pub unsafe fn write_port(port: u16, value: u8) {
+    unsafe {
+        core::arch::asm!(
+            "out dx, al",
+            in("dx") port,
+            in("al") value,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+}
+
+/// Escribe 32 bits a un puerto de I/O (`out dx, eax`)
+///
+/// # Seguridad
+/// Igual que `write_port`: el llamador debe saber qué dispositivo responde
+/// en `port` y que acepta accesos de 32 bits.
+#[inline]
+pub unsafe fn write_port_u32(port: u16, value: u32) {
+    unsafe {
+        core::arch::asm!(
+            "out dx, eax",
+            in("dx") port,
+            in("eax") value,
+            options(nomem, nostack, preserves_flags)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! Puerto serie COM1 (UART 16550)
+//!
+//! Salida de texto por el puerto serie, útil para capturar mensajes desde
+//! el emulador (`-serial stdio` en QEMU) o en máquinas sin pantalla.
+
+use crate::io::ports::{read_port, write_port};
+use crate::sync::{SpinLock, without_interrupts};
+use core::fmt;
+
+/// Puerto base de COM1
+const COM1: u16 = 0x3F8;
+
+/// Offsets de los registros del UART respecto al puerto base
+mod registers {
+    pub const DATA: u16 = 0; // Transmisión / divisor bajo con DLAB
+    pub const INTERRUPT_ENABLE: u16 = 1; // Interrupciones / divisor alto con DLAB
+    pub const FIFO_CONTROL: u16 = 2;
+    pub const LINE_CONTROL: u16 = 3;
+    pub const MODEM_CONTROL: u16 = 4;
+    pub const LINE_STATUS: u16 = 5;
+}
+
+/// Bit del registro de estado de línea: registro de transmisión vacío
+const LINE_STATUS_TRANSMIT_EMPTY: u8 = 0x20;
+
+/// Puerto serie
+pub struct SerialPort {
+    base: u16,
+}
+
+impl SerialPort {
+    /// Crea un puerto para la dirección base indicada (sin inicializarlo)
+    pub const fn new(base: u16) -> Self {
+        Self { base }
+    }
+
+    /// Configura el puerto a 38400 baudios, 8 bits, sin paridad, 1 stop bit
+    pub fn init(&mut self) {
+        unsafe {
+            write_port(self.base + registers::INTERRUPT_ENABLE, 0x00); // Sin interrupciones
+            write_port(self.base + registers::LINE_CONTROL, 0x80); // DLAB para el divisor
+            write_port(self.base + registers::DATA, 0x03); // Divisor 3 = 38400 baudios
+            write_port(self.base + registers::INTERRUPT_ENABLE, 0x00);
+            write_port(self.base + registers::LINE_CONTROL, 0x03); // 8N1
+            write_port(self.base + registers::FIFO_CONTROL, 0xC7); // FIFO habilitada y limpia
+            write_port(self.base + registers::MODEM_CONTROL, 0x0B); // DTR, RTS, OUT2
+        }
+    }
+
+    /// Envía un byte, esperando a que el UART pueda transmitir
+    pub fn write_byte(&mut self, byte: u8) {
+        unsafe {
+            while read_port(self.base + registers::LINE_STATUS) & LINE_STATUS_TRANSMIT_EMPTY == 0 {
+                core::hint::spin_loop();
+            }
+            write_port(self.base + registers::DATA, byte);
+        }
+    }
+}
+
+impl fmt::Write for SerialPort {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for byte in s.bytes() {
+            if byte == b'\n' {
+                self.write_byte(b'\r');
+            }
+            self.write_byte(byte);
+        }
+        Ok(())
+    }
+}
+
+/// Puerto COM1 compartido
+pub static SERIAL: SpinLock<SerialPort> = SpinLock::new(SerialPort::new(COM1));
+
+/// Inicializa COM1
+pub fn init() {
+    without_interrupts(|| SERIAL.lock().init());
+}
+
+/// Escribe texto formateado en COM1
+pub fn write_fmt(args: fmt::Arguments) {
+    use core::fmt::Write;
+
+    without_interrupts(|| {
+        let _ = SERIAL.lock().write_fmt(args);
+    });
+}
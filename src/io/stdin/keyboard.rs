@@ -14,6 +14,7 @@ mod ports {
 mod status_bits {
     pub const OUTPUT_BUFFER_FULL: u8 = 0x01; // Buffer de salida lleno
     pub const INPUT_BUFFER_FULL: u8 = 0x02; // Buffer de entrada lleno
+    pub const AUX_OUTPUT_BUFFER_FULL: u8 = 0x20; // El dato pendiente es del mouse
 }
 
 /// Comandos básicos del teclado
@@ -24,30 +25,56 @@ mod commands {
     pub const ENABLE_KEYBOARD: u8 = 0xAE;
 }
 
-/// Lee un byte desde un puerto de I/O de forma segura
-#[inline]
-unsafe fn read_port(port: u16) -> u8 {
-    let result: u8;
-    core::arch::asm!(
-        "in al, dx",
-        out("al") result,
-        in("dx") port,
-        options(nomem, nostack, preserves_flags)
-    );
-    result
-}
-
-/// Escribe un byte a un puerto de I/O de forma segura
-#[inline]
-unsafe fn write_port(port: u16, value: u8) {
-    core::arch::asm!(
-        "out dx, al",
-        in("dx") port,
-        in("al") value,
-        options(nomem, nostack, preserves_flags)
-    );
+/// Comandos y respuestas del dispositivo (enviados por el puerto de datos)
+mod device {
+    pub const RESET: u8 = 0xFF; // Reinicio y self-test
+    pub const SCANCODE_SET: u8 = 0xF0; // Selecciona el set de scancodes
+    pub const ACK: u8 = 0xFA;
+    pub const RESEND: u8 = 0xFE; // El teclado pide repetir el último byte
+    pub const ERROR: u8 = 0x00; // Error o buffer desbordado
+    pub const ERROR_ALT: u8 = 0xFF; // Igual que ERROR, según el set de scancodes
+    pub const SELF_TEST_PASSED: u8 = 0xAA;
+}
+
+use crate::io::mouse;
+use crate::io::ports::{read_port, write_port};
+use crate::io::stdin::overlay;
+use crate::io::stdout::{WRITER, cp437, width};
+use crate::sync::{SpinLock, without_interrupts};
+use crate::timer;
+use core::sync::atomic::{AtomicU8, AtomicU32, Ordering};
+use heapless::{Deque, Vec};
+
+/// Errores de las operaciones con el controlador del teclado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KbError {
+    /// El controlador no respondió dentro del tiempo de espera
+    Timeout,
+    /// El dispositivo respondió con un byte distinto del esperado (ACK)
+    UnexpectedResponse(u8),
+    /// El self-test del teclado terminó con el código indicado en lugar
+    /// de 0xAA
+    SelfTestFailed(u8),
+    /// El teclado pidió repetir el byte más veces de las permitidas
+    ResendLimit,
+    /// El teclado reportó un error o un desborde de su buffer (0x00/0xFF)
+    DeviceError(u8),
 }
 
+/// Milisegundos máximos al esperar al controlador
+const WAIT_TIMEOUT_MS: u64 = 20;
+
+/// Milisegundos máximos al esperar el resultado del self-test, que en
+/// hardware real puede tardar cientos de milisegundos
+const SELF_TEST_TIMEOUT_MS: u64 = 1000;
+
+/// Pausas máximas al esperar al controlador antes de que arranque el
+/// temporizador
+const WAIT_SPIN_LIMIT: u32 = 1000000;
+
+/// Pausas máximas entre dos consultas al controlador
+const MAX_BACKOFF: u32 = 1024;
+
 /// Verifica si hay datos disponibles en el buffer de salida
 fn is_output_ready() -> bool {
     unsafe {
@@ -64,72 +91,297 @@ fn is_input_ready() -> bool {
     }
 }
 
-/// Espera hasta que el input buffer esté listo
-fn wait_for_input_ready() {
-    let mut timeout = 0;
-    while !is_input_ready() && timeout < 1000000 {
-        core::hint::spin_loop();
-        timeout += 1;
+/// Espera hasta que `ready` sea verdadero
+///
+/// Entre consultas hace PAUSE una cantidad de veces que se duplica en cada
+/// vuelta, hasta `MAX_BACKOFF`: el controlador suele responder enseguida,
+/// y si tarda no tiene sentido leer el puerto de estado sin parar. Con el
+/// temporizador funcionando el plazo se mide en milisegundos; antes de
+/// eso se limita la cantidad total de pausas (`WAIT_SPIN_LIMIT`), que no
+/// corresponde a un tiempo fijo.
+///
+/// # Argumentos
+/// * `ready` - Condición que se consulta
+/// * `timeout_ms` - Plazo en milisegundos si el temporizador funciona
+fn wait_until(ready: fn() -> bool, timeout_ms: u64) -> Result<(), KbError> {
+    let deadline = timer::is_running().then(|| timer::ticks() + timeout_ms);
+    let mut backoff = 1;
+    let mut spins = 0;
+
+    while !ready() {
+        let expired = match deadline {
+            Some(deadline) => timer::ticks() >= deadline,
+            None => spins >= WAIT_SPIN_LIMIT,
+        };
+        if expired {
+            return Err(KbError::Timeout);
+        }
+
+        for _ in 0..backoff {
+            core::hint::spin_loop();
+        }
+        spins += backoff;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
     }
+    Ok(())
+}
+
+/// Espera hasta que el input buffer esté listo
+fn wait_for_input_ready() -> Result<(), KbError> {
+    wait_until(is_input_ready, WAIT_TIMEOUT_MS)
 }
 
 /// Espera hasta que haya datos disponibles
-fn wait_for_output_ready() {
-    let mut timeout = 0;
-    while !is_output_ready() && timeout < 1000000 {
-        core::hint::spin_loop();
-        timeout += 1;
-    }
+fn wait_for_output_ready() -> Result<(), KbError> {
+    wait_until(is_output_ready, WAIT_TIMEOUT_MS)
 }
 
 /// Lee un scancode del teclado (no bloqueante)
+///
+/// Los bytes del puerto auxiliar (mouse) que aparezcan antes se entregan
+/// al decodificador de paquetes del mouse y no se reportan como scancodes.
 pub fn read_scancode() -> Option<u8> {
-    if is_output_ready() {
-        unsafe { Some(read_port(ports::DATA)) }
-    } else {
-        None
+    loop {
+        let status = unsafe { read_port(ports::STATUS_CMD) };
+        if status & status_bits::OUTPUT_BUFFER_FULL == 0 {
+            return None;
+        }
+
+        let byte = unsafe { read_port(ports::DATA) };
+        if status & status_bits::AUX_OUTPUT_BUFFER_FULL != 0 {
+            mouse::handle_byte(byte);
+            continue;
+        }
+
+        return Some(byte);
+    }
+}
+
+/// Espera y lee el siguiente byte del controlador, venga del teclado o
+/// del mouse
+///
+/// Pensado para leer respuestas (ACK, resultados de comandos) durante la
+/// inicialización, cuando todavía no se hace polling.
+pub fn read_data() -> Result<u8, KbError> {
+    wait_for_output_ready()?;
+    unsafe { Ok(read_port(ports::DATA)) }
+}
+
+/// Descarta los datos pendientes en el buffer de salida del controlador
+pub fn flush_output_buffer() {
+    while read_scancode().is_some() {
+        core::hint::spin_loop();
     }
 }
 
 /// Envía un comando al controlador del teclado
-fn send_command(cmd: u8) {
+pub fn send_command(cmd: u8) -> Result<(), KbError> {
+    wait_for_input_ready()?;
     unsafe {
-        wait_for_input_ready();
         write_port(ports::STATUS_CMD, cmd);
     }
+    Ok(())
 }
 
 /// Envía datos al teclado
-fn send_data(data: u8) {
+pub fn send_data(data: u8) -> Result<(), KbError> {
+    wait_for_input_ready()?;
     unsafe {
-        wait_for_input_ready();
         write_port(ports::DATA, data);
     }
+    Ok(())
+}
+
+/// Reinicia el teclado y ejecuta su self-test
+///
+/// Envía 0xFF al dispositivo, espera el ACK (0xFA) y luego el resultado
+/// del self-test, que debe ser 0xAA. El puerto del teclado tiene que estar
+/// habilitado en el controlador.
+///
+/// # Retorna
+/// * `Err(KbError::Timeout)` si el teclado no respondió
+/// * Los errores de `send_data_with_ack` si no llegó el ACK
+/// * `Err(KbError::SelfTestFailed(byte))` si el self-test falló
+pub fn reset() -> Result<(), KbError> {
+    send_data_with_ack(device::RESET)?;
+
+    wait_until(is_output_ready, SELF_TEST_TIMEOUT_MS)?;
+    match unsafe { read_port(ports::DATA) } {
+        device::SELF_TEST_PASSED => Ok(()),
+        result => Err(KbError::SelfTestFailed(result)),
+    }
+}
+
+/// Bit del byte de configuración que habilita la traducción a set 1
+const CONFIG_TRANSLATION: u8 = 0x40;
+
+/// Veces que se reenvía un byte cuando el teclado responde 0xFE
+const MAX_RESENDS: u32 = 3;
+
+/// Envía un byte al teclado y espera su ACK
+///
+/// Si el teclado responde 0xFE (resend) el byte se vuelve a enviar, hasta
+/// `MAX_RESENDS` veces. Debe usarse para todos los comandos al dispositivo;
+/// hoy la usan `reset` y `set_scancode_set`.
+///
+/// # Retorna
+/// * `Err(KbError::ResendLimit)` si el teclado siguió pidiendo reenvío
+/// * `Err(KbError::DeviceError(byte))` si respondió 0x00 o 0xFF
+/// * `Err(KbError::UnexpectedResponse(byte))` ante cualquier otra respuesta
+pub fn send_data_with_ack(data: u8) -> Result<(), KbError> {
+    for _ in 0..=MAX_RESENDS {
+        send_data(data)?;
+
+        match read_data()? {
+            device::ACK => return Ok(()),
+            device::RESEND => continue,
+            error @ (device::ERROR | device::ERROR_ALT) => {
+                return Err(KbError::DeviceError(error));
+            }
+            response => return Err(KbError::UnexpectedResponse(response)),
+        }
+    }
+    Err(KbError::ResendLimit)
+}
+
+/// Selecciona el set de scancodes que envía el teclado (1, 2 o 3)
+///
+/// Con la traducción del controlador activa (como la deja
+/// `init_keyboard`), el set que se debe pedir es el 2: el controlador lo
+/// convierte al set 1 que espera `SCANCODE_MAP`.
+pub fn set_scancode_set(set: u8) -> Result<(), KbError> {
+    send_data_with_ack(device::SCANCODE_SET)?;
+    send_data_with_ack(set)
 }
 
 /// Inicializa el teclado con configuración básica
-pub fn init_keyboard() {
-    unsafe {
-        // Habilitar el teclado
-        send_command(commands::ENABLE_KEYBOARD);
+///
+/// Reinicia el dispositivo antes de configurar el controlador; si el
+/// self-test falla, la inicialización se corta y retorna el error.
+///
+/// Los teclados envían el set 2 por defecto, pero `SCANCODE_MAP` está en
+/// set 1. En lugar de mantener una segunda tabla, se pide el set 2 de
+/// forma explícita (por si el firmware dejó otro) y se activa la
+/// traducción del controlador (bit 6 del byte de configuración), que
+/// convierte el set 2 al set 1 antes de que el byte llegue al puerto de
+/// datos.
+///
+/// # Retorna
+/// El error de `reset` o `Err(KbError::Timeout)` si el controlador dejó de
+/// responder en algún paso
+pub fn init_keyboard() -> Result<(), KbError> {
+    // Habilitar el teclado
+    send_command(commands::ENABLE_KEYBOARD)?;
 
-        // Leer configuración actual
-        send_command(commands::READ_CONFIG);
-        wait_for_output_ready();
+    // Descartar bytes viejos y reiniciar el dispositivo
+    flush_output_buffer();
+    reset()?;
+    set_scancode_set(2)?;
 
-        let config = read_port(ports::DATA);
+    // Leer configuración actual
+    let config = read_config()?;
 
-        // Habilitar interrupciones del teclado y deshabilitar las del mouse
-        let new_config = (config | 0x01) & !0x20;
+    // Habilitar interrupciones del teclado y la traducción a set 1, y
+    // deshabilitar las interrupciones del mouse
+    let new_config = (config | 0x01 | CONFIG_TRANSLATION) & !0x20;
 
-        // Escribir nueva configuración
-        send_command(commands::WRITE_CONFIG);
-        send_data(new_config);
+    // Escribir nueva configuración
+    write_config(new_config)
+}
+
+/// Lee el byte de configuración del controlador
+pub fn read_config() -> Result<u8, KbError> {
+    send_command(commands::READ_CONFIG)?;
+    read_data()
+}
+
+/// Escribe el byte de configuración del controlador
+pub fn write_config(config: u8) -> Result<(), KbError> {
+    send_command(commands::WRITE_CONFIG)?;
+    send_data(config)
+}
+
+/// Genera una tabla de scancode a carácter a partir de pares
+///
+/// Los scancodes que no aparecen en `entries` quedan en `None`; si un
+/// scancode aparece dos veces gana el último. Al ser `const fn`, las
+/// tablas se arman en tiempo de compilación.
+const fn build_map(entries: &[(u8, char)]) -> [Option<char>; 256] {
+    let mut map = [None; 256];
+    let mut index = 0;
+    while index < entries.len() {
+        let (scancode, character) = entries[index];
+        map[scancode as usize] = Some(character);
+        index += 1;
     }
+    map
 }
 
+/// Teclas del layout US básico (set 1)
+const US_LAYOUT: &[(u8, char)] = &[
+    // Números
+    (0x02, '1'),
+    (0x03, '2'),
+    (0x04, '3'),
+    (0x05, '4'),
+    (0x06, '5'),
+    (0x07, '6'),
+    (0x08, '7'),
+    (0x09, '8'),
+    (0x0A, '9'),
+    (0x0B, '0'),
+    // Fila QWERTY
+    (0x10, 'q'),
+    (0x11, 'w'),
+    (0x12, 'e'),
+    (0x13, 'r'),
+    (0x14, 't'),
+    (0x15, 'y'),
+    (0x16, 'u'),
+    (0x17, 'i'),
+    (0x18, 'o'),
+    (0x19, 'p'),
+    // Fila ASDF
+    (0x1E, 'a'),
+    (0x1F, 's'),
+    (0x20, 'd'),
+    (0x21, 'f'),
+    (0x22, 'g'),
+    (0x23, 'h'),
+    (0x24, 'j'),
+    (0x25, 'k'),
+    (0x26, 'l'),
+    // Fila ZXCV
+    (0x2C, 'z'),
+    (0x2D, 'x'),
+    (0x2E, 'c'),
+    (0x2F, 'v'),
+    (0x30, 'b'),
+    (0x31, 'n'),
+    (0x32, 'm'),
+    // Teclas especiales
+    (0x39, ' '),    // Espacio
+    (0x1C, '\n'),   // Enter
+    (0x0E, '\x08'), // Backspace
+];
+
 /// Tabla de conversión de scancode a ASCII (layout US básico)
-const SCANCODE_MAP: [Option<char>; 256] = {
+const SCANCODE_MAP: [Option<char>; 256] = build_map(US_LAYOUT);
+
+/// Indica si `map` convierte `scancode` en `expected`
+const fn maps_to(map: &[Option<char>; 256], scancode: u8, expected: Option<char>) -> bool {
+    match (map[scancode as usize], expected) {
+        (Some(actual), Some(expected)) => actual == expected,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+/// Tabla escrita a mano que `build_map` reemplazó
+///
+/// Se conserva solo para verificar en compilación que la tabla generada es
+/// la misma. Escape ya no produce carácter: se decodifica como `Key::Escape`.
+const LEGACY_SCANCODE_MAP: [Option<char>; 256] = {
     let mut map = [None; 256];
 
     // Números
@@ -177,28 +429,833 @@ const SCANCODE_MAP: [Option<char>; 256] = {
     map[0x32] = Some('m');
 
     // Teclas especiales
+    map[0x01] = Some('\x1b'); // Escape
     map[0x39] = Some(' '); // Espacio
     map[0x1C] = Some('\n'); // Enter
+    map[0x0E] = Some('\x08'); // Backspace
 
     map
 };
 
-/// Convierte un scancode a carácter ASCII
+// Compara las 256 entradas de la tabla generada con la anterior
+const _: () = {
+    let mut scancode = 0;
+    while scancode < 256 {
+        let expected = if scancode == SCANCODE_ESCAPE as usize {
+            None
+        } else {
+            LEGACY_SCANCODE_MAP[scancode]
+        };
+        assert!(maps_to(&SCANCODE_MAP, scancode as u8, expected));
+        scancode += 1;
+    }
+};
+
+/// Tercer nivel (AltGr) de los layouts europeos
+///
+/// Usa las posiciones del layout español, que comparte la mayoría de
+/// ellas con otros layouts europeos. Los caracteres sin equivalente en
+/// CP437 (como '€') se descartan al decodificar, porque el modo texto no
+/// puede mostrarlos.
+const ALTGR_LAYER: &[(u8, char)] = &[
+    (0x02, '|'),
+    (0x03, '@'),
+    (0x04, '#'),
+    (0x05, '~'),
+    (0x07, '¬'),
+    (0x12, '€'),
+    (0x1A, '['),
+    (0x1B, ']'),
+    (0x28, '{'),
+    (0x2B, '}'),
+    (0x29, '\\'),
+];
+
+/// Tabla de conversión de scancode a carácter con AltGr presionado
+const SCANCODE_MAP_ALTGR: [Option<char>; 256] = build_map(ALTGR_LAYER);
+
+/// Cantidad máxima de scancodes reasignados con `override_scancode`
+pub const MAX_SCANCODE_OVERRIDES: usize = 16;
+
+/// Reasignaciones de scancodes hechas en tiempo de ejecución
+static SCANCODE_OVERRIDES: SpinLock<Vec<(u8, char), MAX_SCANCODE_OVERRIDES>> =
+    SpinLock::new(Vec::new());
+
+/// Reasigna el carácter que produce un scancode
+///
+/// Permite corregir una tecla puntual sin recompilar el layout. El orden
+/// de precedencia al decodificar es: reasignación, layout activo y, si
+/// ninguno la mapea, la tecla no produce carácter. Las reasignaciones solo
+/// aplican al nivel base: con AltGr presionado se usa la tabla de AltGr.
+/// Las teclas con evento propio (Escape, Tab, F1-F12) no se reasignan.
+///
+/// # Argumentos
+/// * `scancode` - Scancode (set 1, sin el bit de liberación)
+/// * `ch` - Carácter a producir, o `None` para quitar la reasignación y
+///   volver al layout
+///
+/// # Retorna
+/// `false` si la tabla ya tiene `MAX_SCANCODE_OVERRIDES` reasignaciones
+pub fn override_scancode(scancode: u8, ch: Option<char>) -> bool {
+    let mut overrides = SCANCODE_OVERRIDES.lock();
+    let existing = overrides.iter().position(|&(code, _)| code == scancode);
+
+    match (existing, ch) {
+        (Some(index), Some(character)) => {
+            overrides[index].1 = character;
+            true
+        }
+        (Some(index), None) => {
+            overrides.swap_remove(index);
+            true
+        }
+        (None, Some(character)) => overrides.push((scancode, character)).is_ok(),
+        (None, None) => true,
+    }
+}
+
+/// Reasignación vigente para un scancode, si existe
+fn scancode_override(scancode: u8) -> Option<char> {
+    SCANCODE_OVERRIDES
+        .lock()
+        .iter()
+        .find(|&&(code, _)| code == scancode)
+        .map(|&(_, character)| character)
+}
+
+/// Convierte un scancode a carácter
+///
+/// Primero se consultan las reasignaciones de `override_scancode` y
+/// después el layout. Con AltGr (Alt derecho) presionado se consulta
+/// solo `SCANCODE_MAP_ALTGR`; si la tecla no tiene tercer nivel, no
+/// produce carácter. Con cualquiera de los Shift presionado las letras
+/// ASCII pasan a mayúsculas; el layout no tiene nivel Shift para el resto
+/// de las teclas. El resultado siempre tiene representación en CP437.
+#[inline]
+fn scancode_to_char(scancode: u8, modifiers: KeyboardState) -> Option<char> {
+    let character = if modifiers.ralt {
+        SCANCODE_MAP_ALTGR[scancode as usize]
+    } else {
+        scancode_override(scancode).or(SCANCODE_MAP[scancode as usize])
+    };
+
+    character
+        .map(|character| {
+            if modifiers.shift() {
+                character.to_ascii_uppercase()
+            } else {
+                character
+            }
+        })
+        .filter(|&character| cp437::from_char(character).is_some())
+}
+
+/// Cantidad de teclas de función (F1-F12)
+const FUNCTION_KEY_COUNT: usize = 12;
+
+/// Handler opcional de cada tecla de función, indexado desde F1
+type FunctionKeyHandlers = [Option<fn()>; FUNCTION_KEY_COUNT];
+
+/// Handlers registrados para cada tecla de función
+static FUNCTION_KEY_HANDLERS: SpinLock<FunctionKeyHandlers> =
+    SpinLock::new([None; FUNCTION_KEY_COUNT]);
+
+/// Handlers registrados para cada tecla de función con Alt presionado
+static ALT_FUNCTION_KEY_HANDLERS: SpinLock<FunctionKeyHandlers> =
+    SpinLock::new([None; FUNCTION_KEY_COUNT]);
+
+/// Convierte un scancode al número de tecla de función (1-12)
 #[inline]
-fn scancode_to_char(scancode: u8) -> Option<char> {
-    SCANCODE_MAP.get(scancode as usize).copied().flatten()
+fn function_key_number(scancode: u8) -> Option<u8> {
+    match scancode {
+        0x3B..=0x44 => Some(scancode - 0x3B + 1),
+        0x57 => Some(11),
+        0x58 => Some(12),
+        _ => None,
+    }
+}
+
+/// Registra un handler para una tecla de función
+///
+/// El handler se invoca desde `poll_keyboard` cuando se presiona la tecla,
+/// es decir, desde el loop principal y no desde una interrupción. Como se
+/// ejecuta dentro de la llamada a `poll_keyboard`, el handler no debe
+/// registrar handlers nuevos ni asumir que el loop principal continúa
+/// mientras él se ejecuta. Números fuera de 1-12 se ignoran.
+///
+/// # Argumentos
+/// * `n` - Número de la tecla de función (1 para F1, 12 para F12)
+/// * `handler` - Función a ejecutar al presionar la tecla
+pub fn on_function_key(n: u8, handler: fn()) {
+    if n == 0 || n as usize > FUNCTION_KEY_COUNT {
+        return;
+    }
+
+    FUNCTION_KEY_HANDLERS.lock()[n as usize - 1] = Some(handler);
+}
+
+/// Registra un handler para Alt + una tecla de función
+///
+/// Mismas reglas que `on_function_key`. Con Alt presionado solo se
+/// ejecuta este handler, nunca el de la tecla sola.
+///
+/// # Argumentos
+/// * `n` - Número de la tecla de función (1 para F1, 12 para F12)
+/// * `handler` - Función a ejecutar al presionar Alt + la tecla
+pub fn on_alt_function_key(n: u8, handler: fn()) {
+    if n == 0 || n as usize > FUNCTION_KEY_COUNT {
+        return;
+    }
+
+    ALT_FUNCTION_KEY_HANDLERS.lock()[n as usize - 1] = Some(handler);
+}
+
+/// Ejecuta el handler registrado para una tecla de función, si existe
+///
+/// Con Alt presionado se busca en la tabla de `on_alt_function_key`.
+fn dispatch_function_key(n: u8, modifiers: KeyboardState) {
+    // Se copia el handler para no tener el lock tomado mientras corre
+    let handler = if modifiers.alt() {
+        ALT_FUNCTION_KEY_HANDLERS.lock()[n as usize - 1]
+    } else {
+        FUNCTION_KEY_HANDLERS.lock()[n as usize - 1]
+    };
+
+    if let Some(handler) = handler {
+        handler();
+    }
+}
+
+/// Teclas reconocidas por el decodificador
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Tecla que produce un carácter
+    Char(char),
+    /// Tecla de función F1-F12
+    Function(u8),
+    /// Flecha arriba
+    Up,
+    /// Flecha abajo
+    Down,
+    /// Flecha izquierda
+    Left,
+    /// Flecha derecha
+    Right,
+    /// Tecla Pause
+    Pause,
+    /// Tecla Escape (no produce carácter)
+    Escape,
+    /// Tecla Tab (produce '\t' en `poll_keyboard`)
+    Tab,
+    /// Tecla Insert
+    Insert,
+    /// Tecla Delete (Supr)
+    Delete,
+}
+
+impl Key {
+    /// Carácter que produce la tecla en modo texto, si produce alguno
+    pub const fn to_char(self) -> Option<char> {
+        match self {
+            Key::Char(character) => Some(character),
+            Key::Tab => Some('\t'),
+            _ => None,
+        }
+    }
+}
+
+/// Scancodes de teclas con evento propio en lugar de carácter
+const SCANCODE_ESCAPE: u8 = 0x01;
+const SCANCODE_TAB: u8 = 0x0F;
+
+/// Pulsación de una tecla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    /// Tecla decodificada
+    pub key: Key,
+    /// Scancode que produjo la pulsación (sin el prefijo 0xE0)
+    pub scancode: u8,
+    /// Modificadores presionados al momento de la pulsación
+    pub modifiers: KeyboardState,
+}
+
+/// Teclas modificadoras presionadas
+///
+/// Guarda por separado la tecla izquierda y la derecha de cada
+/// modificador; `shift`, `ctrl` y `alt` combinan ambas. Alt derecho es la
+/// tecla AltGr de los teclados internacionales.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeyboardState {
+    pub lshift: bool,
+    pub rshift: bool,
+    pub lctrl: bool,
+    pub rctrl: bool,
+    pub lalt: bool,
+    pub ralt: bool,
+}
+
+impl KeyboardState {
+    /// Estado sin ningún modificador presionado
+    pub const fn new() -> Self {
+        Self {
+            lshift: false,
+            rshift: false,
+            lctrl: false,
+            rctrl: false,
+            lalt: false,
+            ralt: false,
+        }
+    }
+
+    /// Cualquiera de los dos Shift está presionado
+    pub const fn shift(&self) -> bool {
+        self.lshift || self.rshift
+    }
+
+    /// Cualquiera de los dos Ctrl está presionado
+    pub const fn ctrl(&self) -> bool {
+        self.lctrl || self.rctrl
+    }
+
+    /// Cualquiera de los dos Alt está presionado
+    pub const fn alt(&self) -> bool {
+        self.lalt || self.ralt
+    }
+}
+
+/// Scancodes (sin el bit de liberación) de las teclas modificadoras
+///
+/// Ctrl y Alt derechos usan el mismo scancode que los izquierdos, pero
+/// precedido por 0xE0.
+const SCANCODE_LEFT_SHIFT: u8 = 0x2A;
+const SCANCODE_RIGHT_SHIFT: u8 = 0x36;
+const SCANCODE_CTRL: u8 = 0x1D;
+const SCANCODE_ALT: u8 = 0x38;
+
+/// Modificadores presionados, actualizados por el decodificador
+static KEYBOARD_STATE: SpinLock<KeyboardState> = SpinLock::new(KeyboardState::new());
+
+/// Modificadores presionados actualmente
+///
+/// Refleja los bytes ya leídos del controlador, que pueden ir por delante
+/// de los eventos que siguen en la cola; para saber qué modificadores
+/// acompañaban una pulsación se usa `KeyEvent::modifiers`.
+pub fn keyboard_state() -> KeyboardState {
+    *KEYBOARD_STATE.lock()
+}
+
+/// Actualiza el estado de los modificadores con un byte ya sin prefijo
+///
+/// Los Shift extendidos son "falsos": el teclado los envía alrededor de
+/// algunas teclas extendidas y no corresponden a ninguna tecla física, así
+/// que se ignoran.
+///
+/// # Retorna
+/// `true` si el byte era de una tecla modificadora (presión o liberación)
+fn update_modifiers(scancode: u8, extended: bool) -> bool {
+    let pressed = scancode & 0x80 == 0;
+    let mut state = KEYBOARD_STATE.lock();
+
+    match (scancode & 0x7F, extended) {
+        (SCANCODE_LEFT_SHIFT | SCANCODE_RIGHT_SHIFT, true) => {}
+        (SCANCODE_LEFT_SHIFT, false) => state.lshift = pressed,
+        (SCANCODE_RIGHT_SHIFT, false) => state.rshift = pressed,
+        (SCANCODE_CTRL, false) => state.lctrl = pressed,
+        (SCANCODE_CTRL, true) => state.rctrl = pressed,
+        (SCANCODE_ALT, false) => state.lalt = pressed,
+        (SCANCODE_ALT, true) => state.ralt = pressed,
+        _ => return false,
+    }
+    true
+}
+
+/// Prefijo de los scancodes extendidos (flechas, teclado numérico, etc.)
+const EXTENDED_PREFIX: u8 = 0xE0;
+
+/// Prefijo de la secuencia de la tecla Pause (0xE1 0x1D 0x45 0xE1 0x9D 0xC5)
+const PAUSE_PREFIX: u8 = 0xE1;
+
+/// Bytes que siguen al prefijo 0xE1 en la secuencia de Pause
+const PAUSE_SEQUENCE_TAIL: u8 = 5;
+
+/// Estado del decodificador de scancodes
+///
+/// Los prefijos se consumen dentro del decodificador y nunca se reportan
+/// como teclas: un byte solo produce una tecla cuando su secuencia está
+/// completa.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DecoderState {
+    /// Esperando el primer byte de una secuencia
+    Normal,
+    /// Se recibió 0xE0; el próximo byte es un scancode extendido
+    Extended,
+    /// Dentro de la secuencia de Pause; faltan `remaining` bytes
+    Pause { remaining: u8 },
+}
+
+static DECODER: SpinLock<DecoderState> = SpinLock::new(DecoderState::Normal);
+
+/// Convierte un scancode extendido (precedido por 0xE0) a tecla
+#[inline]
+fn extended_scancode_to_key(scancode: u8) -> Option<Key> {
+    match scancode {
+        0x48 => Some(Key::Up),
+        0x50 => Some(Key::Down),
+        0x4B => Some(Key::Left),
+        0x4D => Some(Key::Right),
+        0x52 => Some(Key::Insert),
+        0x53 => Some(Key::Delete),
+        0x1C => Some(Key::Char('\n')), // Enter del teclado numérico
+        0x35 => Some(Key::Char('/')),  // '/' del teclado numérico
+        _ => None,
+    }
+}
+
+/// Decodifica un byte recibido del teclado
+///
+/// Retorna None para prefijos, bytes intermedios de una secuencia,
+/// modificadores, liberaciones de tecla y teclas sin mapear. Los
+/// modificadores solo actualizan el estado de `modifiers`.
+fn decode_scancode(scancode: u8) -> Option<Key> {
+    let mut state = DECODER.lock();
+
+    match *state {
+        DecoderState::Pause { remaining } => {
+            // La secuencia de Pause no tiene liberación: se reporta al final
+            if remaining > 1 {
+                *state = DecoderState::Pause {
+                    remaining: remaining - 1,
+                };
+                return None;
+            }
+            *state = DecoderState::Normal;
+            return Some(Key::Pause);
+        }
+        DecoderState::Extended => {
+            *state = DecoderState::Normal;
+
+            // Un prefijo repetido no cambia el estado
+            if scancode == EXTENDED_PREFIX {
+                *state = DecoderState::Extended;
+                return None;
+            }
+            if update_modifiers(scancode, true) || scancode & 0x80 != 0 {
+                return None;
+            }
+            return extended_scancode_to_key(scancode);
+        }
+        DecoderState::Normal => {}
+    }
+
+    match scancode {
+        EXTENDED_PREFIX => {
+            *state = DecoderState::Extended;
+            None
+        }
+        PAUSE_PREFIX => {
+            *state = DecoderState::Pause {
+                remaining: PAUSE_SEQUENCE_TAIL,
+            };
+            None
+        }
+        modifier if update_modifiers(modifier, false) => None,
+        // Liberación de tecla
+        released if released & 0x80 != 0 => None,
+        pressed => {
+            if let Some(n) = function_key_number(pressed) {
+                return Some(Key::Function(n));
+            }
+            match pressed {
+                SCANCODE_ESCAPE => Some(Key::Escape),
+                SCANCODE_TAB => Some(Key::Tab),
+                _ => scancode_to_char(pressed, keyboard_state()).map(Key::Char),
+            }
+        }
+    }
+}
+
+/// Capacidad de la cola de teclas pendientes
+const KEY_QUEUE_CAPACITY: usize = 32;
+
+/// Cola (ring buffer) de pulsaciones ya decodificadas, en orden de llegada
+///
+/// Es la frontera entre el hardware y los consumidores: `drain_controller`
+/// pasa los bytes crudos por el decodificador (prefijos, modificadores,
+/// layout y AltGr) y solo encola teclas completas, mientras que
+/// `poll_keyboard_event`, `poll_keyboard` y `peek_event` leen de acá. Una
+/// secuencia recibida a medias (un 0xE0 sin su segundo byte, o la
+/// secuencia de Pause incompleta) queda en el estado del decodificador y
+/// no produce ningún evento hasta que llega el resto.
+static KEY_QUEUE: SpinLock<Deque<KeyEvent, KEY_QUEUE_CAPACITY>> = SpinLock::new(Deque::new());
+
+/// Lee todos los bytes pendientes del controlador y los decodifica
+///
+/// El controlador solo guarda un byte, así que leer uno por iteración del
+/// loop principal pierde teclas al escribir rápido. Aquí se vacía por
+/// completo y las pulsaciones se guardan en la cola; las liberaciones y
+/// los prefijos se consumen en el decodificador. Si la cola está llena se
+/// aplica la `QueueOverflowPolicy` configurada y el descarte se cuenta en
+/// `dropped_count`. Los bytes del mouse se entregan a su propio
+/// decodificador. Los bytes de error del teclado se descartan y se
+/// cuentan en `overrun_count`.
+pub fn drain_controller() {
+    while let Some(scancode) = read_scancode() {
+        if is_overrun(scancode) {
+            record_overrun(scancode);
+            continue;
+        }
+
+        let key = decode_scancode(scancode);
+        record_scancode(scancode, key);
+
+        if let Some(key) = key {
+            enqueue(KeyEvent {
+                key,
+                scancode,
+                modifiers: keyboard_state(),
+            });
+        }
+    }
+}
+
+/// Errores o desbordes del buffer interno del teclado desde el arranque
+static OVERRUN_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Verifica si un byte recibido es un aviso de error del teclado
+///
+/// El teclado envía 0x00 (set 1) o 0xFF (set 2) cuando su buffer se
+/// desborda o detecta un error. Ninguno es un scancode válido: 0xFF
+/// parecería la liberación de una tecla y 0x00 no está mapeado.
+fn is_overrun(byte: u8) -> bool {
+    byte == device::ERROR || byte == device::ERROR_ALT
+}
+
+/// Cuenta un aviso de error y descarta la secuencia en curso
+///
+/// Un desborde pudo perder bytes a mitad de una secuencia con prefijo,
+/// así que el decodificador vuelve a esperar el primer byte.
+fn record_overrun(byte: u8) {
+    OVERRUN_COUNT.fetch_add(1, Ordering::Relaxed);
+    *DECODER.lock() = DecoderState::Normal;
+    record_scancode(byte, None);
+}
+
+/// Cantidad de avisos de error o desborde recibidos del teclado
+pub fn overrun_count() -> u32 {
+    OVERRUN_COUNT.load(Ordering::Relaxed)
+}
+
+/// Byte recibido del teclado y la tecla que produjo, si produjo alguna
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScancodeRecord {
+    /// Byte tal como llegó del controlador (prefijos y liberaciones
+    /// incluidos)
+    pub scancode: u8,
+    /// Tecla decodificada con este byte
+    pub key: Option<Key>,
+}
+
+/// Cantidad de bytes recientes que guarda `scancode_history`
+pub const SCANCODE_HISTORY_CAPACITY: usize = 8;
+
+/// Últimos bytes recibidos, del más viejo al más nuevo
+static SCANCODE_HISTORY: SpinLock<Deque<ScancodeRecord, SCANCODE_HISTORY_CAPACITY>> =
+    SpinLock::new(Deque::new());
+
+/// Agrega un byte al historial, descartando el más viejo si está lleno
+fn record_scancode(scancode: u8, key: Option<Key>) {
+    let mut history = SCANCODE_HISTORY.lock();
+    if history.is_full() {
+        history.pop_front();
+    }
+    let _ = history.push_back(ScancodeRecord { scancode, key });
+}
+
+/// Últimos bytes recibidos del teclado, del más viejo al más nuevo
+///
+/// A diferencia de la cola de eventos incluye prefijos, liberaciones y
+/// teclas sin mapear, así que sirve para diagnosticar teclas que no
+/// producen nada.
+pub fn scancode_history() -> Deque<ScancodeRecord, SCANCODE_HISTORY_CAPACITY> {
+    SCANCODE_HISTORY.lock().clone()
+}
+
+/// Qué evento se descarta cuando la cola de teclas está llena
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum QueueOverflowPolicy {
+    /// Se descarta el evento nuevo (las teclas viejas se conservan)
+    DropNewest,
+    /// Se descarta el evento más viejo para hacer lugar al nuevo
+    DropOldest,
+}
+
+static OVERFLOW_POLICY: AtomicU8 = AtomicU8::new(QueueOverflowPolicy::DropNewest as u8);
+
+/// Eventos descartados por tener la cola llena desde el arranque
+static DROPPED_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// Cambia qué evento se descarta cuando la cola está llena
+///
+/// Por defecto es `QueueOverflowPolicy::DropNewest`.
+pub fn set_overflow_policy(policy: QueueOverflowPolicy) {
+    OVERFLOW_POLICY.store(policy as u8, Ordering::Relaxed);
+}
+
+/// Cantidad de eventos descartados por tener la cola llena
+pub fn dropped_count() -> u32 {
+    DROPPED_COUNT.load(Ordering::Relaxed)
+}
+
+/// Agrega un evento a la cola aplicando la política de desborde
+fn enqueue(event: KeyEvent) {
+    let mut queue = KEY_QUEUE.lock();
+    if !queue.is_full() {
+        let _ = queue.push_back(event);
+        return;
+    }
+
+    DROPPED_COUNT.fetch_add(1, Ordering::Relaxed);
+    if OVERFLOW_POLICY.load(Ordering::Relaxed) == QueueOverflowPolicy::DropOldest as u8 {
+        queue.pop_front();
+        let _ = queue.push_back(event);
+    }
+}
+
+/// Obtiene la siguiente pulsación de tecla mediante polling (no bloqueante)
+///
+/// A diferencia de `poll_keyboard`, también reporta teclas que no producen
+/// caracteres, como las flechas. Las teclas de función se despachan a sus
+/// handlers registrados antes de retornar el evento, y Ctrl+Alt+K alterna
+/// el overlay de depuración (`overlay::toggle`) sin reportarse.
+pub fn poll_keyboard_event() -> Option<KeyEvent> {
+    drain_controller();
+
+    loop {
+        let event = KEY_QUEUE.lock().pop_front()?;
+
+        if is_overlay_hotkey(&event) {
+            overlay::toggle();
+            continue;
+        }
+        if let Key::Function(n) = event.key {
+            dispatch_function_key(n, event.modifiers);
+        }
+
+        return Some(event);
+    }
+}
+
+/// Ctrl+Alt+K muestra u oculta el overlay de depuración del teclado
+///
+/// La combinación se consume: no llega a quien lee los eventos.
+fn is_overlay_hotkey(event: &KeyEvent) -> bool {
+    event.key == Key::Char('k') && event.modifiers.ctrl() && event.modifiers.alt()
 }
 
 /// Obtiene entrada del teclado mediante polling (no bloqueante)
 ///
 /// Retorna Some(char) si se presionó una tecla válida,
-/// None si no hay entrada o la tecla no está mapeada
+/// None si no hay entrada o la tecla no produce un carácter. Las teclas de
+/// función se despachan a sus handlers registrados y retornan None.
 pub fn poll_keyboard() -> Option<char> {
-    if let Some(scancode) = read_scancode() {
-        // Verificar que es una pulsación (no liberación de tecla)
-        if scancode & 0x80 == 0 {
-            return scancode_to_char(scancode);
+    poll_keyboard_event()?.key.to_char()
+}
+
+/// Consulta el siguiente evento de teclado sin consumirlo
+///
+/// Retorna el primer evento de la cola; la próxima llamada a
+/// `poll_keyboard_event` o `poll_keyboard` retorna el mismo evento. Las
+/// teclas de función no se despachan hasta que el evento se consume.
+///
+/// Solo funciona porque la entrada pasa por la cola de software: el
+/// controlador no permite leer un byte sin sacarlo del puerto de datos.
+pub fn peek_event() -> Option<KeyEvent> {
+    drain_controller();
+    KEY_QUEUE.lock().front().copied()
+}
+
+/// Cantidad de eventos ya decodificados que esperan en la cola
+///
+/// No cuenta una secuencia que todavía no llegó completa.
+pub fn queued_events() -> usize {
+    drain_controller();
+    KEY_QUEUE.lock().len()
+}
+
+/// Consulta el siguiente carácter sin consumirlo
+///
+/// Retorna None si la cola está vacía o si el primer evento no produce un
+/// carácter (por ejemplo, una flecha), igual que `poll_keyboard`.
+pub fn peek_key() -> Option<char> {
+    peek_event()?.key.to_char()
+}
+
+/// Handler que recibe los eventos de teclado en `service`
+static INPUT_HANDLER: SpinLock<Option<fn(KeyEvent)>> = SpinLock::new(None);
+
+/// Instala el handler que procesa los eventos de teclado
+///
+/// Retorna el handler anterior para que una interfaz modal pueda
+/// devolverlo con `restore_input_handler` al terminar.
+///
+/// El handler se ejecuta fuera del lock y nunca en paralelo consigo
+/// mismo, pero cuando la entrada pase a ser por interrupciones correrá
+/// desde la ISR: no debe bloquear ni esperar otra pulsación.
+pub fn set_input_handler(handler: fn(KeyEvent)) -> Option<fn(KeyEvent)> {
+    INPUT_HANDLER.lock().replace(handler)
+}
+
+/// Vuelve a instalar un handler retornado por `set_input_handler`
+///
+/// Con `None` se quita el handler y `service` deja de consumir eventos.
+pub fn restore_input_handler(previous: Option<fn(KeyEvent)>) {
+    *INPUT_HANDLER.lock() = previous;
+}
+
+/// Entrega al handler instalado todos los eventos pendientes
+///
+/// Sin handler no consume nada, así que los eventos siguen disponibles
+/// para `poll_keyboard_event`. El handler se vuelve a consultar antes de
+/// cada evento, por lo que un cambio hecho desde el propio handler se
+/// aplica al siguiente.
+pub fn service() {
+    loop {
+        let Some(handler) = *INPUT_HANDLER.lock() else {
+            return;
+        };
+        let Some(event) = poll_keyboard_event() else {
+            return;
+        };
+        handler(event);
+    }
+}
+
+/// Cómo se muestra lo que se escribe en `read_line_masked`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Echo {
+    /// Se muestra cada carácter tal cual
+    Plain,
+    /// Se muestra el carácter indicado en lugar de cada carácter
+    Mask(u8),
+    /// No se muestra nada
+    Hidden,
+}
+
+/// Lee una línea del teclado mostrando lo que se escribe
+///
+/// Bloquea hasta que se presiona Enter. Backspace borra el último
+/// carácter del buffer y de la pantalla. Los caracteres que no entran en
+/// `buf` se ignoran. El eco usa el writer global (`WRITER`). Los bytes
+/// quedan en CP437, así que los caracteres de AltGr como '¬' no son UTF-8.
+///
+/// # Retorna
+/// La cantidad de bytes escritos en `buf`, sin el salto de línea
+pub fn read_line(buf: &mut [u8]) -> usize {
+    read_line_with_echo(buf, Echo::Plain, None).unwrap_or(0)
+}
+
+/// Lee una línea del teclado con un tiempo límite
+///
+/// Igual que `read_line`, pero deja de esperar si no se presiona Enter
+/// dentro de `timeout_ms` milisegundos desde la llamada (medidos con
+/// `timer::ticks`). El plazo no se reinicia con cada tecla.
+///
+/// Al vencer el plazo la entrada parcial se descarta: `buf` puede
+/// conservar los bytes ya escritos, pero no forman una línea válida. Lo
+/// que se mostró en pantalla queda visible y el writer pasa a la línea
+/// siguiente, igual que con Enter.
+///
+/// # Retorna
+/// La cantidad de bytes escritos en `buf`, o `None` si se venció el plazo
+pub fn read_line_timeout(buf: &mut [u8], timeout_ms: u64) -> Option<usize> {
+    let deadline = timer::ticks() + timeout_ms;
+    read_line_with_echo(buf, Echo::Plain, Some(deadline))
+}
+
+/// Lee una línea del teclado sin mostrar lo que se escribe
+///
+/// Igual que `read_line`, pero en pantalla se muestra `mask` por cada
+/// carácter, o nada si `mask` es `None`. Backspace también borra el
+/// carácter de máscara correspondiente.
+pub fn read_line_masked(buf: &mut [u8], mask: Option<u8>) -> usize {
+    let echo = match mask {
+        Some(mask) => Echo::Mask(mask),
+        None => Echo::Hidden,
+    };
+    read_line_with_echo(buf, echo, None).unwrap_or(0)
+}
+
+/// Loop común de las lecturas de línea
+///
+/// `deadline` es el tick (`timer::ticks`) en el que se deja de esperar;
+/// sin plazo solo retorna al presionar Enter.
+fn read_line_with_echo(buf: &mut [u8], echo: Echo, deadline: Option<u64>) -> Option<usize> {
+    let mut len = 0;
+
+    loop {
+        let Some(character) = poll_keyboard() else {
+            if deadline.is_some_and(|deadline| timer::ticks() >= deadline) {
+                echo_newline();
+                return None;
+            }
+            core::hint::spin_loop();
+            continue;
+        };
+
+        match character {
+            '\n' => {
+                echo_newline();
+                return Some(len);
+            }
+            '\x08' => {
+                if len > 0 {
+                    len -= 1;
+                    if echo != Echo::Hidden {
+                        erase_last_echo();
+                    }
+                }
+            }
+            c if !c.is_control() && len < buf.len() => {
+                let byte = cp437::from_char_lossy(c);
+                buf[len] = byte;
+                len += 1;
+
+                match echo {
+                    Echo::Plain => echo_byte(byte),
+                    Echo::Mask(mask) => echo_byte(mask),
+                    Echo::Hidden => {}
+                }
+            }
+            _ => {}
         }
     }
-    None
+}
+
+/// Muestra un byte en el writer global
+fn echo_byte(byte: u8) {
+    without_interrupts(|| WRITER.lock().write_char(byte));
+}
+
+/// Pasa el writer global a la línea siguiente
+fn echo_newline() {
+    without_interrupts(|| WRITER.lock().write_string(b"\n"));
+}
+
+/// Borra el último carácter mostrado por el writer global
+fn erase_last_echo() {
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        let (x, y) = writer.position();
+
+        let (x, y) = match (x, y) {
+            (0, 0) => return,
+            (0, y) => (width() as u8 - 1, y - 1),
+            (x, y) => (x - 1, y),
+        };
+
+        writer.set_position(x, y);
+        writer.write_char(b' ');
+        writer.set_position(x, y);
+    });
 }
@@ -0,0 +1,138 @@
+//! Edición de una línea de texto
+//!
+//! `LineEditor` guarda el texto en un buffer de tamaño fijo y lo redibuja
+//! en su posición de pantalla después de cada tecla. Soporta inserción en
+//! el medio de la línea, Backspace, Delete y las flechas izquierda/derecha.
+//! La tecla Insert alterna entre insertar y sobrescribir.
+
+use crate::io::stdin::keyboard::{Key, KeyEvent};
+use crate::io::stdout::cursor::{CURSOR_BLOCK, CURSOR_UNDERLINE};
+use crate::io::stdout::{TextCursor, cp437, set_cursor_position, set_cursor_shape, width};
+use crate::log;
+use heapless::Vec;
+
+/// Carácter que produce la tecla Backspace
+const BACKSPACE: char = '\x08';
+
+/// Editor de una línea de hasta `N` bytes
+pub struct LineEditor<const N: usize> {
+    buffer: Vec<u8, N>,
+    /// Posición de edición dentro del buffer
+    cursor: usize,
+    /// Celda donde se dibuja el primer carácter
+    origin: (u8, u8),
+    color: u8,
+    /// Si es verdadero, escribir reemplaza el carácter bajo el cursor
+    overwrite: bool,
+}
+
+impl<const N: usize> LineEditor<N> {
+    /// Crea un editor vacío en `(0, 0)`
+    pub const fn new(color: u8) -> Self {
+        Self {
+            buffer: Vec::new(),
+            cursor: 0,
+            origin: (0, 0),
+            color,
+            overwrite: false,
+        }
+    }
+
+    /// Empieza una línea nueva en la posición indicada
+    ///
+    /// Descarta el texto anterior y mueve el cursor de hardware al inicio.
+    pub fn begin(&mut self, x: u8, y: u8) {
+        self.buffer.clear();
+        self.cursor = 0;
+        self.origin = (x, y);
+        self.redraw();
+    }
+
+    /// Cambia entre sobrescribir e insertar
+    ///
+    /// El cursor de hardware lo indica con su forma: bloque al sobrescribir
+    /// y subrayado al insertar. Al final de la línea siempre se agrega.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+
+        let (start, end) = if overwrite {
+            CURSOR_BLOCK
+        } else {
+            CURSOR_UNDERLINE
+        };
+        set_cursor_shape(start, end);
+    }
+
+    /// Cambia el color del texto (se aplica en el próximo redibujado)
+    pub fn set_color(&mut self, color: u8) {
+        self.color = color;
+    }
+
+    /// Procesa una tecla
+    ///
+    /// # Retorna
+    /// `true` si se presionó Enter y la línea está lista en `line()`
+    pub fn handle(&mut self, event: KeyEvent) -> bool {
+        match event.key {
+            Key::Char('\n') => return true,
+            Key::Char(BACKSPACE) => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                    self.buffer.remove(self.cursor);
+                }
+            }
+            Key::Delete => {
+                // Al final de la línea no hay nada que borrar
+                if self.cursor < self.buffer.len() {
+                    self.buffer.remove(self.cursor);
+                }
+            }
+            Key::Char(c) if !c.is_control() => {
+                let byte = cp437::from_char_lossy(c);
+                if self.overwrite && self.cursor < self.buffer.len() {
+                    self.buffer[self.cursor] = byte;
+                    self.cursor += 1;
+                } else if self.buffer.insert(self.cursor, byte).is_ok() {
+                    self.cursor += 1;
+                }
+            }
+            Key::Insert => self.set_overwrite(!self.overwrite),
+            Key::Left => self.cursor = self.cursor.saturating_sub(1),
+            Key::Right => self.cursor = (self.cursor + 1).min(self.buffer.len()),
+            _ => return false,
+        }
+
+        self.redraw();
+        false
+    }
+
+    /// Texto de la línea actual, en CP437
+    pub fn line(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Celda justo después del último carácter de la línea
+    pub fn end_position(&self) -> (u8, u8) {
+        (self.origin.0 + self.buffer.len() as u8, self.origin.1)
+    }
+
+    /// Dibuja la línea, borra lo que quedaba a la derecha y ubica el
+    /// cursor de hardware en la posición de edición
+    fn redraw(&self) {
+        let (x, y) = self.origin;
+
+        // Ni el cursor de hardware ni `end_position` contemplan que la
+        // línea siga en la fila de abajo
+        log::soft_assert!(
+            x as usize + self.buffer.len() < width(),
+            "line editor text past the end of row {}",
+            y
+        );
+
+        let mut cursor = TextCursor::new(x, y, self.color);
+        cursor.write_string(&self.buffer);
+        cursor.clear_to_eol();
+
+        set_cursor_position(x + self.cursor as u8, y);
+    }
+}
@@ -1 +1,3 @@
 pub mod keyboard;
+pub mod line;
+pub mod overlay;
@@ -0,0 +1,106 @@
+//! Overlay de depuración del teclado
+//!
+//! Muestra en la esquina superior derecha los últimos bytes recibidos del
+//! teclado, la tecla que decodificó cada uno y los modificadores
+//! presionados. Se alterna con Ctrl+Alt+K y se actualiza en cada vuelta
+//! del loop principal como una tarea cooperativa. Sirve para diagnosticar
+//! teclas que no producen nada o un layout equivocado.
+
+use crate::io::stdin::keyboard::{SCANCODE_HISTORY_CAPACITY, keyboard_state, scancode_history};
+use crate::io::stdout::colors::{BLUE, WHITE, YELLOW, make_color};
+use crate::io::stdout::{RegionWriter, restore_region, save_region, width};
+use crate::sync::SpinLock;
+use crate::task;
+use core::fmt::Write;
+
+/// Ancho del overlay en columnas
+const OVERLAY_WIDTH: u8 = 24;
+
+/// Alto del overlay: título, modificadores y una fila por byte
+const OVERLAY_HEIGHT: u8 = 2 + SCANCODE_HISTORY_CAPACITY as u8;
+
+const OVERLAY_COLOR: u8 = make_color(WHITE, BLUE);
+
+/// Color del título, para distinguirlo de los datos
+const TITLE_COLOR: u8 = make_color(YELLOW, BLUE);
+
+/// Pantalla tapada por el overlay mientras está visible
+struct Saved {
+    x: u8,
+    cells: [u16; OVERLAY_WIDTH as usize * OVERLAY_HEIGHT as usize],
+}
+
+static SAVED: SpinLock<Option<Saved>> = SpinLock::new(None);
+
+/// Indica si el overlay está en pantalla
+pub fn is_visible() -> bool {
+    SAVED.lock().is_some()
+}
+
+/// Muestra el overlay, o lo oculta si ya estaba visible
+///
+/// Al mostrarlo se guarda la región que tapa y se registra la tarea que lo
+/// redibuja; al ocultarlo se restaura esa región. Si no hay lugar para
+/// otra tarea, el overlay se dibuja una vez pero no se actualiza.
+pub fn toggle() {
+    let mut saved = SAVED.lock();
+
+    if let Some(previous) = saved.take() {
+        drop(saved);
+        task::unregister(render);
+        restore_region(
+            previous.x,
+            0,
+            OVERLAY_WIDTH,
+            OVERLAY_HEIGHT,
+            &previous.cells,
+        );
+        return;
+    }
+
+    let x = (width() as u8).saturating_sub(OVERLAY_WIDTH);
+    let mut cells = [0; OVERLAY_WIDTH as usize * OVERLAY_HEIGHT as usize];
+    save_region(x, 0, OVERLAY_WIDTH, OVERLAY_HEIGHT, &mut cells);
+    *saved = Some(Saved { x, cells });
+    drop(saved);
+
+    task::register(render);
+    render();
+}
+
+/// Dibuja el contenido actual del overlay
+fn render() {
+    let Some(x) = SAVED.lock().as_ref().map(|saved| saved.x) else {
+        return;
+    };
+
+    let mut region = RegionWriter::new(x, 0, OVERLAY_WIDTH, OVERLAY_HEIGHT, OVERLAY_COLOR);
+    region.clear();
+
+    let state = keyboard_state();
+    let side = |left: bool, right: bool| match (left, right) {
+        (true, true) => "LR",
+        (true, false) => "L-",
+        (false, true) => "-R",
+        (false, false) => "--",
+    };
+
+    region.set_color(TITLE_COLOR);
+    let _ = writeln!(region, " Keyboard (Ctrl+Alt+K)");
+    region.set_color(OVERLAY_COLOR);
+    let _ = writeln!(
+        region,
+        " S:{} C:{} A:{}",
+        side(state.lshift, state.rshift),
+        side(state.lctrl, state.rctrl),
+        side(state.lalt, state.ralt),
+    );
+
+    for record in scancode_history().iter().rev() {
+        let _ = write!(region, " {:02X} ", record.scancode);
+        let _ = match record.key {
+            Some(key) => writeln!(region, "{:?}", key),
+            None => writeln!(region, "-"),
+        };
+    }
+}
@@ -0,0 +1,629 @@
+//! Abstracción del almacenamiento de celdas de la pantalla
+//!
+//! Las rutinas de dibujo (limpiar, desplazar, escribir caracteres) se
+//! escriben contra el trait `VgaBackend`, de modo que funcionan igual sobre
+//! el buffer VGA real (`HardwareVga`) y sobre un arreglo en memoria
+//! (`ArrayBackend`), que permite verificar el resultado exacto sin
+//! hardware.
+
+use crate::io::stdout::cp437::box_drawing;
+use crate::io::stdout::geometry::Rect;
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{
+    OverflowPolicy, TextCursor, clamp_coordinate, height, make_cell, try_write_char_at, vga_base,
+    width, write_centered_on, write_right_aligned_on,
+};
+
+/// Almacenamiento de celdas (carácter + color) organizado en filas
+///
+/// Los índices son lineales: `y * width() + x`.
+pub trait VgaBackend {
+    /// Ancho en columnas
+    fn width(&self) -> usize;
+
+    /// Alto en filas
+    fn height(&self) -> usize;
+
+    /// Lee la celda en `index`
+    fn read_cell(&self, index: usize) -> u16;
+
+    /// Escribe la celda en `index`
+    fn write_cell(&mut self, index: usize, cell: u16);
+
+    /// Cantidad total de celdas
+    fn cells(&self) -> usize {
+        self.width().saturating_mul(self.height())
+    }
+
+    /// Escribe un carácter en su posición
+    ///
+    /// # Retorna
+    /// `false` si el carácter queda fuera de los límites
+    fn put_char(&mut self, syschar: SysPrintableChar) -> bool {
+        if !syschar.is_valid_in(self.width(), self.height()) {
+            return false;
+        }
+
+        let index = syschar.y as usize * self.width() + syschar.x as usize;
+        self.write_cell(index, make_cell(syschar.character, syschar.color));
+        true
+    }
+
+    /// Llena todas las celdas con espacios del color indicado
+    fn clear(&mut self, color: u8) {
+        let blank = make_cell(b' ', color);
+        for index in 0..self.cells() {
+            self.write_cell(index, blank);
+        }
+    }
+
+    /// Copia la fila `from` completa sobre la fila `to`
+    ///
+    /// Una fila fuera de `height()` no se copia; en builds de depuración
+    /// es un error del llamador.
+    fn copy_row(&mut self, from: usize, to: usize) {
+        debug_assert!(from < self.height() && to < self.height());
+        if from >= self.height() || to >= self.height() {
+            return;
+        }
+
+        let row = self.width();
+        for col in 0..row {
+            let cell = self.read_cell(from * row + col);
+            self.write_cell(to * row + col, cell);
+        }
+    }
+
+    /// Llena la fila `row` con la celda indicada
+    ///
+    /// Igual que `copy_row`, una fila fuera de `height()` se ignora.
+    fn fill_row(&mut self, row: usize, cell: u16) {
+        debug_assert!(row < self.height());
+        if row >= self.height() {
+            return;
+        }
+
+        let width = self.width();
+        for col in 0..width {
+            self.write_cell(row * width + col, cell);
+        }
+    }
+
+    /// Escribe `count` copias de un carácter hacia la derecha desde `(x, y)`
+    ///
+    /// La corrida se recorta una sola vez al borde derecho; si la fila está
+    /// fuera de `height()` no se escribe nada.
+    fn fill_horizontal(&mut self, character: u8, color: u8, x: u8, y: u8, count: u8) {
+        if y as usize >= self.height() {
+            return;
+        }
+        let width = self.width();
+        let end = (x as usize + count as usize).min(width);
+        let cell = make_cell(character, color);
+
+        for col in x as usize..end {
+            self.write_cell(y as usize * width + col, cell);
+        }
+    }
+
+    /// Escribe `count` copias de un carácter hacia abajo desde `(x, y)`
+    ///
+    /// Igual que `fill_horizontal`, pero la corrida se recorta al borde
+    /// inferior.
+    fn fill_vertical(&mut self, character: u8, color: u8, x: u8, y: u8, count: u8) {
+        let width = self.width();
+        if x as usize >= width {
+            return;
+        }
+        let end = (y as usize + count as usize).min(self.height());
+        let cell = make_cell(character, color);
+
+        for row in y as usize..end {
+            self.write_cell(row * width + x as usize, cell);
+        }
+    }
+
+    /// Dibuja el borde de una caja con caracteres de línea simple
+    ///
+    /// El interior no se modifica. Las partes del borde fuera del backend
+    /// se ignoran; una región de menos de 2x2 no dibuja nada.
+    fn draw_box(&mut self, rect: Rect, color: u8) {
+        if rect.w < 2 || rect.h < 2 {
+            return;
+        }
+
+        let right = rect.x.saturating_add(rect.w - 1);
+        let bottom = rect.y.saturating_add(rect.h - 1);
+
+        // Con la caja pegada al borde de las coordenadas `u8`, el interior
+        // satura en lugar de desbordar y queda fuera de la pantalla
+        let inner_x = rect.x.saturating_add(1);
+        let inner_y = rect.y.saturating_add(1);
+        let inner_w = rect.w - 2;
+        let inner_h = rect.h - 2;
+        self.fill_horizontal(box_drawing::HORIZONTAL, color, inner_x, rect.y, inner_w);
+        self.fill_horizontal(box_drawing::HORIZONTAL, color, inner_x, bottom, inner_w);
+        self.fill_vertical(box_drawing::VERTICAL, color, rect.x, inner_y, inner_h);
+        self.fill_vertical(box_drawing::VERTICAL, color, right, inner_y, inner_h);
+
+        let corners = [
+            (rect.x, rect.y, box_drawing::TOP_LEFT),
+            (right, rect.y, box_drawing::TOP_RIGHT),
+            (rect.x, bottom, box_drawing::BOTTOM_LEFT),
+            (right, bottom, box_drawing::BOTTOM_RIGHT),
+        ];
+        for (x, y, character) in corners {
+            self.put_char(SysPrintableChar::new(character, color, x, y));
+        }
+    }
+
+    /// Distribuye un texto con las reglas de `SysPrintableChar::new_string`
+    ///
+    /// El wrapping y los límites usan las dimensiones del backend.
+    fn new_string(
+        &self,
+        buffer: &[u8],
+        color: u8,
+        start_x: u8,
+        start_y: u8,
+    ) -> heapless::Vec<SysPrintableChar, 2000> {
+        let size = (self.width(), self.height());
+        SysPrintableChar::layout(buffer.iter().copied(), color, start_x, start_y, size, false).0
+    }
+
+    /// Distribuye un texto con `new_string` y lo escribe
+    fn write_string(&mut self, buffer: &[u8], color: u8, start_x: u8, start_y: u8) {
+        for syschar in self.new_string(buffer, color, start_x, start_y) {
+            self.put_char(syschar);
+        }
+    }
+
+    /// Desplaza el contenido una línea hacia arriba
+    ///
+    /// La primera línea se descarta y la última queda en blanco.
+    fn scroll_up(&mut self, blank_color: u8) {
+        self.scroll_rows_up(0, self.height(), 1, blank_color);
+    }
+
+    /// Desplaza las filas `top..bottom` `lines` líneas hacia arriba
+    ///
+    /// Las primeras `lines` filas del rango se descartan y las últimas
+    /// quedan en blanco con el color indicado. Si `lines` es mayor que el
+    /// rango, el rango completo queda en blanco. Las filas fuera del rango
+    /// no se modifican.
+    ///
+    /// Los rangos se calculan con `height()` del backend, nunca con las
+    /// constantes del modo 80x25, así que un buffer cuyo tamaño no es
+    /// múltiplo del ancho solo usa sus filas completas.
+    fn scroll_rows_up(&mut self, top: usize, bottom: usize, lines: usize, blank_color: u8) {
+        let bottom = bottom.min(self.height());
+        if top >= bottom || lines == 0 {
+            return;
+        }
+        let lines = lines.min(bottom - top);
+        debug_assert!(top + lines <= bottom && bottom <= self.height());
+
+        for row in top..bottom - lines {
+            self.copy_row(row + lines, row);
+        }
+
+        let blank = make_cell(b' ', blank_color);
+        for row in bottom - lines..bottom {
+            self.fill_row(row, blank);
+        }
+    }
+
+    /// Desplaza las filas `top..bottom` `lines` líneas hacia abajo
+    ///
+    /// Las últimas `lines` filas del rango se descartan y las primeras
+    /// quedan en blanco con el color indicado. Si `lines` es mayor que el
+    /// rango, el rango completo queda en blanco.
+    fn scroll_rows_down(&mut self, top: usize, bottom: usize, lines: usize, blank_color: u8) {
+        let bottom = bottom.min(self.height());
+        if top >= bottom || lines == 0 {
+            return;
+        }
+        let lines = lines.min(bottom - top);
+        debug_assert!(top + lines <= bottom && bottom <= self.height());
+
+        for row in (top + lines..bottom).rev() {
+            self.copy_row(row - lines, row);
+        }
+
+        let blank = make_cell(b' ', blank_color);
+        for row in top..top + lines {
+            self.fill_row(row, blank);
+        }
+    }
+}
+
+/// Buffer de texto VGA real
+///
+/// Usa la dirección detectada por `init_vga` y las dimensiones actuales
+/// de `width()`/`height()`.
+pub struct HardwareVga;
+
+impl VgaBackend for HardwareVga {
+    fn width(&self) -> usize {
+        width()
+    }
+
+    fn height(&self) -> usize {
+        height()
+    }
+
+    fn read_cell(&self, index: usize) -> u16 {
+        if index >= self.cells() {
+            return 0;
+        }
+        unsafe { core::ptr::read_volatile((vga_base() as *const u16).add(index)) }
+    }
+
+    fn write_cell(&mut self, index: usize, cell: u16) {
+        if index >= self.cells() {
+            return;
+        }
+        unsafe {
+            core::ptr::write_volatile((vga_base() as *mut u16).add(index), cell);
+        }
+    }
+}
+
+/// Pantalla virtual respaldada por un arreglo de `N` celdas
+///
+/// Permite ejecutar las rutinas de dibujo sin acceso al hardware y
+/// comparar el contenido resultante celda por celda.
+pub struct ArrayBackend<const N: usize> {
+    cells: [u16; N],
+    width: usize,
+}
+
+impl<const N: usize> ArrayBackend<N> {
+    /// Crea una pantalla virtual de `width` columnas, vacía (celdas en 0)
+    ///
+    /// El alto es `N / width`.
+    pub const fn new(width: usize) -> Self {
+        Self {
+            cells: [0; N],
+            width,
+        }
+    }
+
+    /// Contenido actual de todas las celdas
+    pub fn as_cells(&self) -> &[u16; N] {
+        &self.cells
+    }
+}
+
+impl<const N: usize> VgaBackend for ArrayBackend<N> {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn height(&self) -> usize {
+        N.checked_div(self.width).unwrap_or(0)
+    }
+
+    fn read_cell(&self, index: usize) -> u16 {
+        self.cells.get(index).copied().unwrap_or(0)
+    }
+
+    fn write_cell(&mut self, index: usize, cell: u16) {
+        if let Some(slot) = self.cells.get_mut(index) {
+            *slot = cell;
+        }
+    }
+}
+
+/// Pantalla virtual de `W` x `H` celdas con dimensiones fijas
+///
+/// Igual que `ArrayBackend`, pero el ancho y el alto se fijan en tiempo de
+/// compilación: el wrapping de `write_string` y `write_fmt_on` y los
+/// límites de `put_char` usan `W` y `H`, como `new_string_sized`.
+pub struct SizedBackend<const W: usize, const H: usize> {
+    rows: [[u16; W]; H],
+}
+
+impl<const W: usize, const H: usize> SizedBackend<W, H> {
+    /// Crea una pantalla virtual vacía (celdas en 0)
+    pub const fn new() -> Self {
+        Self { rows: [[0; W]; H] }
+    }
+
+    /// Contenido actual, fila por fila
+    pub fn as_rows(&self) -> &[[u16; W]; H] {
+        &self.rows
+    }
+}
+
+impl<const W: usize, const H: usize> VgaBackend for SizedBackend<W, H> {
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn read_cell(&self, index: usize) -> u16 {
+        let Some(row) = index.checked_div(W) else {
+            return 0;
+        };
+        self.rows
+            .get(row)
+            .and_then(|cells| cells.get(index % W))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn write_cell(&mut self, index: usize, cell: u16) {
+        let Some(row) = index.checked_div(W) else {
+            return;
+        };
+        if let Some(slot) = self
+            .rows
+            .get_mut(row)
+            .and_then(|cells| cells.get_mut(index % W))
+        {
+            *slot = cell;
+        }
+    }
+
+    fn put_char(&mut self, syschar: SysPrintableChar) -> bool {
+        if !syschar.is_valid_sized::<W, H>() {
+            return false;
+        }
+
+        self.rows[syschar.y as usize][syschar.x as usize] =
+            make_cell(syschar.character, syschar.color);
+        true
+    }
+}
+
+/// Verifica las rutinas de dibujo sobre pantallas virtuales pequeñas
+///
+/// Cada caso dibuja sobre un `ArrayBackend` y compara el contenido
+/// celda por celda. Se ejecuta al arrancar porque el kernel no tiene tests
+/// de host.
+///
+/// # Retorna
+/// `true` si todos los casos dan el resultado esperado
+pub fn self_check() -> bool {
+    check_scroll()
+        && check_box()
+        && check_string()
+        && check_last_cell()
+        && check_cursor_scroll()
+        && check_tab_stops()
+        && check_overflow_policies()
+        && check_clamped_alignment()
+}
+
+/// Desplaza una pantalla de 4x3 con valores distintos en cada celda
+///
+/// Cada fila debe quedar con el contenido de la siguiente y la última en
+/// blanco.
+fn check_scroll() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 3;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    for index in 0..CELLS {
+        screen.write_cell(index, make_cell(b'A' + index as u8, COLOR));
+    }
+
+    screen.scroll_up(COLOR);
+
+    let cells = screen.as_cells();
+    let shifted = (0..CELLS - WIDTH)
+        .all(|index| cells[index] == make_cell(b'A' + (index + WIDTH) as u8, COLOR));
+    let blanked = cells[CELLS - WIDTH..]
+        .iter()
+        .all(|&cell| cell == make_cell(b' ', COLOR));
+    shifted && blanked
+}
+
+/// Dibuja una caja de 4x3 que se sale por la derecha de una pantalla de 5x4
+///
+/// Las esquinas y los bordes visibles deben quedar en su celda, el
+/// interior y la fila de abajo sin tocar, y la parte de la caja fuera de
+/// la pantalla recortada.
+fn check_box() -> bool {
+    const WIDTH: usize = 5;
+    const CELLS: usize = WIDTH * 4;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    screen.draw_box(Rect::new(2, 0, 4, 3), COLOR);
+
+    let at = |x: usize, y: usize| screen.read_cell(y * WIDTH + x);
+    let cell = |character| make_cell(character, COLOR);
+
+    // Solo las columnas 2-4 están en pantalla: la esquina derecha
+    // (columna 5) se recorta
+    at(2, 0) == cell(box_drawing::TOP_LEFT)
+        && at(3, 0) == cell(box_drawing::HORIZONTAL)
+        && at(4, 0) == cell(box_drawing::HORIZONTAL)
+        && at(2, 1) == cell(box_drawing::VERTICAL)
+        && at(3, 1) == 0
+        && at(4, 1) == 0
+        && at(2, 2) == cell(box_drawing::BOTTOM_LEFT)
+        && at(4, 2) == cell(box_drawing::HORIZONTAL)
+        && (0..WIDTH).all(|x| at(x, 3) == 0)
+        && (0..3).all(|y| at(0, y) == 0 && at(1, y) == 0)
+}
+
+/// Escribe un texto con wrapping y salto de línea en una pantalla de 4x2
+///
+/// "abcde" llena la fila 0 y la 'e' pasa a la fila 1; el texto después
+/// del salto de línea cae debajo de la última fila y se descarta.
+fn check_string() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 2;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    screen.write_string(b"abcde\nxy", COLOR, 0, 0);
+
+    let expected = *b"abcde\0\0\0";
+    let cells = screen.as_cells();
+    (0..CELLS).all(|index| match expected[index] {
+        0 => cells[index] == 0,
+        character => cells[index] == make_cell(character, COLOR),
+    })
+}
+
+/// Escribe en la esquina inferior derecha de una pantalla de 4x3 y del
+/// buffer VGA
+///
+/// La última celda debe poder escribirse y la columna siguiente no. En el
+/// buffer real se escribe con `try_write_char_at` y después se restaura
+/// el contenido original.
+fn check_last_cell() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 3;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    let in_virtual = screen.put_char(SysPrintableChar::new(b'Z', COLOR, 3, 2))
+        && screen.as_cells()[CELLS - 1] == make_cell(b'Z', COLOR)
+        && !screen.put_char(SysPrintableChar::new(b'Z', COLOR, 4, 2));
+
+    let mut vga = HardwareVga;
+    let index = vga.cells() - 1;
+    let original = vga.read_cell(index);
+    let (right, bottom) = ((width() - 1) as u8, (height() - 1) as u8);
+    let in_hardware = try_write_char_at(SysPrintableChar::new(b'Z', COLOR, right, bottom))
+        && vga.read_cell(index) == make_cell(b'Z', COLOR)
+        && !try_write_char_at(SysPrintableChar::new(b'Z', COLOR, right + 1, bottom));
+    vga.write_cell(index, original);
+
+    in_virtual && in_hardware
+}
+
+/// Llena una pantalla de 4x2 con un `TextCursor` y escribe un carácter más
+///
+/// El cursor pasa a la fila siguiente al llegar a la columna 3 y, en la
+/// última fila, el carácter siguiente desplaza la pantalla en lugar de
+/// sobrescribirla.
+fn check_cursor_scroll() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 2;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    let mut cursor = TextCursor::new(0, 0, COLOR);
+    cursor.write_string_on(&mut screen, b"abcdefgh");
+    let filled = cursor.position() == (4, 1) && matches_text(&screen, b"abcdefgh", COLOR);
+
+    cursor.write_string_on(&mut screen, b"i");
+    let scrolled = cursor.position() == (1, 1) && matches_text(&screen, b"efghi   ", COLOR);
+
+    filled && scrolled
+}
+
+/// Avanza un `TextCursor` con `tab` desde varias columnas de una pantalla
+/// de 12x2
+///
+/// Las paradas cuentan desde la columna 0, no desde donde empezó el
+/// cursor, y las celdas intermedias quedan con espacios. Con la parada en
+/// el borde derecho, el carácter siguiente va a la fila de abajo.
+fn check_tab_stops() -> bool {
+    const WIDTH: usize = 12;
+    const CELLS: usize = WIDTH * 2;
+    const COLOR: u8 = 0x07;
+
+    let stops = [(0, 4), (3, 4), (4, 8), (5, 8), (8, 12), (10, 12)];
+    let aligned = stops.iter().all(|&(start, stop)| {
+        let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+        let mut cursor = TextCursor::new(start, 0, COLOR);
+        cursor.tab_on(&mut screen);
+
+        let padded =
+            (start as usize..stop as usize).all(|x| screen.read_cell(x) == make_cell(b' ', COLOR));
+        cursor.position() == (stop, 0) && padded
+    });
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    let mut cursor = TextCursor::new(10, 0, COLOR);
+    cursor.tab_on(&mut screen);
+    cursor.write_string_on(&mut screen, b"x");
+    let wrapped = cursor.position() == (1, 1) && screen.read_cell(WIDTH) == make_cell(b'x', COLOR);
+
+    aligned && wrapped
+}
+
+/// Escribe más filas de las que entran en una pantalla de 4x2 con cada
+/// `OverflowPolicy`
+///
+/// La política se aplica igual con un salto de línea explícito que con el
+/// wrapping en el borde derecho: `Scroll` desplaza, `Clamp` sobrescribe
+/// la última fila y `Wrap` vuelve a la primera.
+fn check_overflow_policies() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 2;
+    const COLOR: u8 = 0x07;
+
+    /// Política, texto, celdas esperadas y posición final del cursor
+    type Case = (
+        OverflowPolicy,
+        &'static [u8],
+        &'static [u8; CELLS],
+        (u8, u8),
+    );
+
+    let cases: [Case; 6] = [
+        (OverflowPolicy::Scroll, b"ab\ncd\nef", b"cd  ef  ", (2, 1)),
+        (OverflowPolicy::Clamp, b"ab\ncd\nef", b"ab  ef  ", (2, 1)),
+        (OverflowPolicy::Wrap, b"ab\ncd\nef", b"ef  cd  ", (2, 0)),
+        (OverflowPolicy::Scroll, b"abcdefghi", b"efghi   ", (1, 1)),
+        (OverflowPolicy::Clamp, b"abcdefghi", b"abcdifgh", (1, 1)),
+        (OverflowPolicy::Wrap, b"abcdefghi", b"ibcdefgh", (1, 0)),
+    ];
+    cases.iter().all(|&(policy, text, expected, position)| {
+        let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+        screen.clear(COLOR);
+        let mut cursor = TextCursor::new(0, 0, COLOR);
+        cursor.set_overflow_policy(policy);
+        cursor.write_string_on(&mut screen, text);
+
+        cursor.position() == position && matches_text(&screen, expected, COLOR)
+    })
+}
+
+/// Centra y alinea a la derecha textos más anchos que una pantalla de 4x2
+///
+/// La columna calculada sería negativa, así que el texto debe empezar en
+/// la columna 0 y recortarse, no aparecer en una columna arbitraria. Una
+/// caja cuyas coordenadas saturan en `u8` no dibuja nada en pantalla.
+fn check_clamped_alignment() -> bool {
+    const WIDTH: usize = 4;
+    const CELLS: usize = WIDTH * 2;
+    const COLOR: u8 = 0x07;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    write_centered_on(&mut screen, b"abcdef", COLOR, 0);
+    write_right_aligned_on(&mut screen, b"uvwxyz", COLOR, 1);
+    let oversized = matches_text(&screen, b"abcduvwx", COLOR);
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    write_centered_on(&mut screen, b"ab", COLOR, 0);
+    let centered = screen.read_cell(0) == 0
+        && screen.read_cell(1) == make_cell(b'a', COLOR)
+        && screen.read_cell(2) == make_cell(b'b', COLOR)
+        && screen.read_cell(3) == 0;
+
+    let clamped = clamp_coordinate((80 - 100) / 2, 80) == 0 && clamp_coordinate(200, 80) == 79;
+
+    let mut screen = ArrayBackend::<CELLS>::new(WIDTH);
+    screen.draw_box(Rect::new(250, 250, 10, 10), COLOR);
+    let untouched = screen.as_cells().iter().all(|&cell| cell == 0);
+
+    oversized && centered && clamped && untouched
+}
+
+/// Compara cada celda con un carácter de `text` en el color indicado
+fn matches_text<const N: usize>(screen: &ArrayBackend<N>, text: &[u8; N], color: u8) -> bool {
+    (0..N).all(|index| screen.as_cells()[index] == make_cell(text[index], color))
+}
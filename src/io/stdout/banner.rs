@@ -0,0 +1,115 @@
+//! Texto en tamaño grande para pantallas de bienvenida
+//!
+//! Cada letra se dibuja como un patrón de 5x5 celdas usando el bloque
+//! completo de CP437. Solo hay glifos para letras mayúsculas, dígitos y
+//! espacio; los demás caracteres se dibujan como un espacio.
+
+use crate::io::stdout::Point;
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::try_write_char_at;
+
+/// Ancho de un glifo en celdas
+pub const GLYPH_WIDTH: u8 = 5;
+
+/// Alto de un glifo en celdas
+pub const GLYPH_HEIGHT: u8 = 5;
+
+/// Columnas vacías entre glifos
+pub const GLYPH_SPACING: u8 = 1;
+
+/// Bloque completo en CP437
+const FULL_BLOCK: u8 = 0xDB;
+
+/// Glifo vacío, usado para el espacio y los caracteres sin soporte
+const BLANK: [u8; 5] = [0; 5];
+
+/// Glifos de 'A' a 'Z'; cada fila usa los 5 bits bajos, bit 4 a la izquierda
+const LETTERS: [[u8; 5]; 26] = [
+    [0b01110, 0b10001, 0b11111, 0b10001, 0b10001], // A
+    [0b11110, 0b10001, 0b11110, 0b10001, 0b11110], // B
+    [0b01111, 0b10000, 0b10000, 0b10000, 0b01111], // C
+    [0b11110, 0b10001, 0b10001, 0b10001, 0b11110], // D
+    [0b11111, 0b10000, 0b11110, 0b10000, 0b11111], // E
+    [0b11111, 0b10000, 0b11110, 0b10000, 0b10000], // F
+    [0b01111, 0b10000, 0b10011, 0b10001, 0b01111], // G
+    [0b10001, 0b10001, 0b11111, 0b10001, 0b10001], // H
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b11111], // I
+    [0b00111, 0b00001, 0b00001, 0b10001, 0b01110], // J
+    [0b10001, 0b10010, 0b11100, 0b10010, 0b10001], // K
+    [0b10000, 0b10000, 0b10000, 0b10000, 0b11111], // L
+    [0b10001, 0b11011, 0b10101, 0b10001, 0b10001], // M
+    [0b10001, 0b11001, 0b10101, 0b10011, 0b10001], // N
+    [0b01110, 0b10001, 0b10001, 0b10001, 0b01110], // O
+    [0b11110, 0b10001, 0b11110, 0b10000, 0b10000], // P
+    [0b01110, 0b10001, 0b10101, 0b10010, 0b01101], // Q
+    [0b11110, 0b10001, 0b11110, 0b10010, 0b10001], // R
+    [0b01111, 0b10000, 0b01110, 0b00001, 0b11110], // S
+    [0b11111, 0b00100, 0b00100, 0b00100, 0b00100], // T
+    [0b10001, 0b10001, 0b10001, 0b10001, 0b01110], // U
+    [0b10001, 0b10001, 0b10001, 0b01010, 0b00100], // V
+    [0b10001, 0b10001, 0b10101, 0b11011, 0b10001], // W
+    [0b10001, 0b01010, 0b00100, 0b01010, 0b10001], // X
+    [0b10001, 0b01010, 0b00100, 0b00100, 0b00100], // Y
+    [0b11111, 0b00010, 0b00100, 0b01000, 0b11111], // Z
+];
+
+/// Glifos de '0' a '9'
+const DIGITS: [[u8; 5]; 10] = [
+    [0b01110, 0b10011, 0b10101, 0b11001, 0b01110], // 0
+    [0b00100, 0b01100, 0b00100, 0b00100, 0b01110], // 1
+    [0b11110, 0b00001, 0b01110, 0b10000, 0b11111], // 2
+    [0b11110, 0b00001, 0b00110, 0b00001, 0b11110], // 3
+    [0b10010, 0b10010, 0b11111, 0b00010, 0b00010], // 4
+    [0b11111, 0b10000, 0b11110, 0b00001, 0b11110], // 5
+    [0b01110, 0b10000, 0b11110, 0b10001, 0b01110], // 6
+    [0b11111, 0b00001, 0b00010, 0b00100, 0b00100], // 7
+    [0b01110, 0b10001, 0b01110, 0b10001, 0b01110], // 8
+    [0b01110, 0b10001, 0b01111, 0b00001, 0b01110], // 9
+];
+
+/// Devuelve el glifo de un carácter
+fn glyph(character: u8) -> &'static [u8; 5] {
+    match character {
+        b'A'..=b'Z' => &LETTERS[(character - b'A') as usize],
+        b'0'..=b'9' => &DIGITS[(character - b'0') as usize],
+        _ => &BLANK,
+    }
+}
+
+/// Dibuja texto en tamaño grande
+///
+/// Cada carácter ocupa `GLYPH_WIDTH` x `GLYPH_HEIGHT` celdas más
+/// `GLYPH_SPACING` columnas de separación. Solo se escriben las celdas
+/// encendidas del glifo, así que el fondo existente se conserva. Las
+/// celdas fuera de la pantalla se recortan; el texto nunca pasa a la
+/// línea siguiente.
+///
+/// # Argumentos
+/// * `text` - Texto a dibujar (mayúsculas, dígitos y espacios)
+/// * `x` - Columna de la esquina superior izquierda
+/// * `y` - Fila de la esquina superior izquierda
+/// * `color` - Color de los bloques
+pub fn draw_banner(text: &[u8], x: u8, y: u8, color: u8) {
+    let advance = (GLYPH_WIDTH + GLYPH_SPACING) as usize;
+
+    for (index, &character) in text.iter().enumerate() {
+        let Some(left) = u8::try_from(x as usize + index * advance).ok() else {
+            break;
+        };
+
+        for (row, bits) in glyph(character).iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                    continue;
+                }
+
+                let (Some(cx), Some(cy)) = (left.checked_add(col), y.checked_add(row as u8)) else {
+                    continue;
+                };
+                if Point::new(cx, cy).is_on_screen() {
+                    try_write_char_at(SysPrintableChar::new(FULL_BLOCK, color, cx, cy));
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,9 @@
 //! Definiciones de colores para el modo texto VGA
 
+use crate::io::ports::{read_port, write_port};
+use crate::io::stdout::{VGA_MONO_BUFFER, vga_base};
+use core::sync::atomic::{AtomicBool, Ordering};
+
 /// Colores básicos VGA (4 bits cada uno)
 pub const BLACK: u8 = 0x0;
 pub const BLUE: u8 = 0x1;
@@ -23,6 +27,38 @@ pub const fn make_color(foreground: u8, background: u8) -> u8 {
     (foreground & 0x0F) | ((background & 0x0F) << 4)
 }
 
+/// Bit alto del byte de atributo: parpadeo o fondo brillante
+///
+/// Su significado depende de `set_blink_enabled`: con el parpadeo
+/// habilitado (valor por defecto del BIOS) el carácter parpadea y el fondo
+/// solo puede usar los colores 0x0-0x7; con el parpadeo deshabilitado el
+/// bit selecciona los fondos brillantes 0x8-0xF.
+pub const ATTRIBUTE_HIGH_BIT: u8 = 0x80;
+
+/// Marca un color para que el carácter parpadee
+///
+/// Solo tiene efecto visible con el parpadeo habilitado; en caso
+/// contrario se ve como `with_bright_bg`.
+pub const fn with_blink(color: u8) -> u8 {
+    color | ATTRIBUTE_HIGH_BIT
+}
+
+/// Marca un color para usar la variante brillante del fondo
+///
+/// Solo tiene efecto visible con el parpadeo deshabilitado; en caso
+/// contrario se ve como `with_blink`.
+pub const fn with_bright_bg(color: u8) -> u8 {
+    color | ATTRIBUTE_HIGH_BIT
+}
+
+/// Crea un byte de color con el carácter parpadeando
+///
+/// El fondo se limita a los colores 0x0-0x7, ya que el bit alto del
+/// nibble de fondo pasa a ser el bit de parpadeo.
+pub const fn make_color_blink(foreground: u8, background: u8) -> u8 {
+    with_blink(make_color(foreground, background & 0x07))
+}
+
 /// Extrae el color de foreground de forma segura
 pub const fn get_foreground(color_byte: u8) -> u8 {
     color_byte & 0x0F
@@ -52,3 +88,191 @@ pub const fn make_color_safe(foreground: u8, background: u8) -> u8 {
     };
     make_color(safe_fg, safe_bg)
 }
+
+/// Reemplaza el foreground de un color conservando el background
+///
+/// Igual que `make_color_safe`, un foreground inválido pasa a
+/// `LIGHT_GRAY`.
+pub const fn with_foreground(color: u8, foreground: u8) -> u8 {
+    make_color_safe(foreground, get_background(color))
+}
+
+/// Reemplaza el background de un color conservando el foreground
+///
+/// Igual que `make_color_safe`, un background inválido pasa a `BLACK`.
+pub const fn with_background(color: u8, background: u8) -> u8 {
+    make_color_safe(get_foreground(color), background)
+}
+
+/// Atributo de subrayado de los adaptadores monocromos
+///
+/// En modo monocromo el byte de atributo no es un par de colores: un
+/// foreground 0x1 (bits 0-2 en 001) con background 0x0 se muestra
+/// subrayado, y el bit 3 sigue indicando intensidad. En los adaptadores de
+/// color el mismo valor es simplemente azul sobre negro, así que el
+/// subrayado no existe en modo color.
+pub const MONO_UNDERLINE: u8 = 0x01;
+
+/// Resalta un color de forma adecuada al adaptador en uso
+///
+/// Con un adaptador monocromo devuelve el atributo de subrayado,
+/// conservando la intensidad y el bit alto (parpadeo). Con uno de color,
+/// donde no hay subrayado, usa la variante brillante del foreground
+/// (bit 3); un color que ya es brillante no cambia.
+pub fn emphasize(color: u8) -> u8 {
+    if vga_base() == VGA_MONO_BUFFER {
+        (color & (ATTRIBUTE_HIGH_BIT | 0x08)) | MONO_UNDERLINE
+    } else {
+        color | 0x08
+    }
+}
+
+/// Puertos del hardware VGA usados para la paleta
+mod vga_ports {
+    pub const ATTRIBUTE_CONTROLLER: u16 = 0x3C0; // Índice/dato del Attribute Controller
+    pub const DAC_WRITE_INDEX: u16 = 0x3C8; // Índice de escritura del DAC
+    pub const DAC_DATA: u16 = 0x3C9; // Datos del DAC (R, G, B)
+    pub const INPUT_STATUS_1: u16 = 0x3DA; // Leerlo reinicia el flip-flop del AC
+    pub const ATTRIBUTE_DATA_READ: u16 = 0x3C1; // Lectura de datos del AC
+}
+
+/// Registro Mode Control del Attribute Controller
+const ATTRIBUTE_MODE_CONTROL: u8 = 0x10;
+
+/// Bit del registro Mode Control que habilita el parpadeo
+const MODE_CONTROL_BLINK: u8 = 0x08;
+
+/// Bit del índice del Attribute Controller que mantiene el video encendido
+const ATTRIBUTE_PALETTE_SOURCE: u8 = 0x20;
+
+/// Indica si los registros de paleta del Attribute Controller ya apuntan
+/// a las entradas 0-15 del DAC
+static PALETTE_MAPPED: AtomicBool = AtomicBool::new(false);
+
+/// Paleta estándar VGA/EGA (componentes de 6 bits, en orden de índice)
+pub const DEFAULT_PALETTE: [(u8, u8, u8); 16] = [
+    (0x00, 0x00, 0x00), // BLACK
+    (0x00, 0x00, 0x2A), // BLUE
+    (0x00, 0x2A, 0x00), // GREEN
+    (0x00, 0x2A, 0x2A), // CYAN
+    (0x2A, 0x00, 0x00), // RED
+    (0x2A, 0x00, 0x2A), // MAGENTA
+    (0x2A, 0x15, 0x00), // BROWN
+    (0x2A, 0x2A, 0x2A), // LIGHT_GRAY
+    (0x15, 0x15, 0x15), // DARK_GRAY
+    (0x15, 0x15, 0x3F), // LIGHT_BLUE
+    (0x15, 0x3F, 0x15), // LIGHT_GREEN
+    (0x15, 0x3F, 0x3F), // LIGHT_CYAN
+    (0x3F, 0x15, 0x15), // LIGHT_RED
+    (0x3F, 0x15, 0x3F), // LIGHT_MAGENTA
+    (0x3F, 0x3F, 0x15), // YELLOW
+    (0x3F, 0x3F, 0x3F), // WHITE
+];
+
+/// Paleta Solarized (componentes de 6 bits)
+///
+/// Sigue la asignación de Solarized para terminales de 16 colores,
+/// reordenada a los índices VGA.
+pub const SOLARIZED_PALETTE: [(u8, u8, u8); 16] = [
+    (0x01, 0x0D, 0x10), // BLACK = base02
+    (0x09, 0x22, 0x34), // BLUE = blue
+    (0x21, 0x26, 0x00), // GREEN = green
+    (0x0A, 0x28, 0x26), // CYAN = cyan
+    (0x37, 0x0C, 0x0B), // RED = red
+    (0x34, 0x0D, 0x20), // MAGENTA = magenta
+    (0x2D, 0x22, 0x00), // BROWN = yellow
+    (0x3B, 0x3A, 0x35), // LIGHT_GRAY = base2
+    (0x00, 0x0A, 0x0D), // DARK_GRAY = base03
+    (0x20, 0x25, 0x25), // LIGHT_BLUE = base0
+    (0x16, 0x1B, 0x1D), // LIGHT_GREEN = base01
+    (0x24, 0x28, 0x28), // LIGHT_CYAN = base1
+    (0x32, 0x12, 0x05), // LIGHT_RED = orange
+    (0x1B, 0x1C, 0x31), // LIGHT_MAGENTA = violet
+    (0x19, 0x1E, 0x20), // YELLOW = base00
+    (0x3F, 0x3D, 0x38), // WHITE = base3
+];
+
+/// Hace que cada color 0-15 use la entrada del DAC con el mismo índice
+///
+/// Por defecto el Attribute Controller asigna algunos colores a entradas
+/// altas del DAC (por ejemplo BROWN usa la 0x14 y los colores claros
+/// las 0x38-0x3F). Tras esta llamada, `set_palette_entry(i, ...)` cambia
+/// directamente el color `i`.
+pub fn map_attribute_palette() {
+    unsafe {
+        // Reiniciar el flip-flop índice/dato del Attribute Controller
+        read_port(vga_ports::INPUT_STATUS_1);
+
+        for index in 0..16u8 {
+            write_port(vga_ports::ATTRIBUTE_CONTROLLER, index);
+            write_port(vga_ports::ATTRIBUTE_CONTROLLER, index);
+        }
+
+        // Volver a habilitar el video
+        write_port(vga_ports::ATTRIBUTE_CONTROLLER, ATTRIBUTE_PALETTE_SOURCE);
+    }
+
+    PALETTE_MAPPED.store(true, Ordering::Relaxed);
+}
+
+/// Habilita o deshabilita el parpadeo de caracteres en todo el modo texto
+///
+/// Con el parpadeo deshabilitado, el bit alto del atributo selecciona los
+/// fondos brillantes en lugar de hacer parpadear el carácter (ver
+/// `ATTRIBUTE_HIGH_BIT`).
+pub fn set_blink_enabled(enabled: bool) {
+    unsafe {
+        read_port(vga_ports::INPUT_STATUS_1);
+        write_port(
+            vga_ports::ATTRIBUTE_CONTROLLER,
+            ATTRIBUTE_MODE_CONTROL | ATTRIBUTE_PALETTE_SOURCE,
+        );
+        let mode = read_port(vga_ports::ATTRIBUTE_DATA_READ);
+
+        let new_mode = if enabled {
+            mode | MODE_CONTROL_BLINK
+        } else {
+            mode & !MODE_CONTROL_BLINK
+        };
+        write_port(vga_ports::ATTRIBUTE_CONTROLLER, new_mode);
+    }
+}
+
+/// Cambia el color RGB de una entrada de la paleta
+///
+/// # Argumentos
+/// * `index` - Índice del color (0x0 - 0xF)
+/// * `r`, `g`, `b` - Componentes de 6 bits (0-63); los bits altos se ignoran
+pub fn set_palette_entry(index: u8, r: u8, g: u8, b: u8) {
+    if !is_valid_color(index) {
+        return;
+    }
+
+    if !PALETTE_MAPPED.load(Ordering::Relaxed) {
+        map_attribute_palette();
+    }
+
+    unsafe {
+        write_port(vga_ports::DAC_WRITE_INDEX, index);
+        write_port(vga_ports::DAC_DATA, r & 0x3F);
+        write_port(vga_ports::DAC_DATA, g & 0x3F);
+        write_port(vga_ports::DAC_DATA, b & 0x3F);
+    }
+}
+
+/// Carga una paleta completa de 16 colores
+pub fn load_palette(palette: &[(u8, u8, u8); 16]) {
+    for (index, &(r, g, b)) in palette.iter().enumerate() {
+        set_palette_entry(index as u8, r, g, b);
+    }
+}
+
+/// Restaura la paleta estándar VGA
+pub fn load_default_palette() {
+    load_palette(&DEFAULT_PALETTE);
+}
+
+/// Carga la paleta Solarized
+pub fn load_solarized_palette() {
+    load_palette(&SOLARIZED_PALETTE);
+}
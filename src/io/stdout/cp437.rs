@@ -0,0 +1,55 @@
+//! Conversión de caracteres Unicode a la página de códigos 437
+//!
+//! El modo texto VGA usa la página de códigos 437 (CP437): los bytes 0x00-0x7F
+//! coinciden con ASCII y los bytes 0x80-0xFF contienen letras acentuadas,
+//! caracteres de dibujo de cajas y símbolos.
+
+/// Caracteres de dibujo de cajas de línea simple
+pub mod box_drawing {
+    pub const HORIZONTAL: u8 = 0xC4; // ─
+    pub const VERTICAL: u8 = 0xB3; // │
+    pub const TOP_LEFT: u8 = 0xDA; // ┌
+    pub const TOP_RIGHT: u8 = 0xBF; // ┐
+    pub const BOTTOM_LEFT: u8 = 0xC0; // └
+    pub const BOTTOM_RIGHT: u8 = 0xD9; // ┘
+    pub const TEE_DOWN: u8 = 0xC2; // ┬
+    pub const TEE_UP: u8 = 0xC1; // ┴
+    pub const TEE_RIGHT: u8 = 0xC3; // ├
+    pub const TEE_LEFT: u8 = 0xB4; // ┤
+    pub const CROSS: u8 = 0xC5; // ┼
+}
+
+/// Byte usado para los caracteres que no existen en CP437
+pub const REPLACEMENT: u8 = b'?';
+
+/// Caracteres Unicode de la mitad alta de CP437 (bytes 0x80-0xFF)
+const HIGH_HALF: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å', //
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ', //
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»', //
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐', //
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧', //
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀', //
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩', //
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{a0}',
+];
+
+/// Convierte un carácter Unicode a su byte CP437 equivalente
+///
+/// # Retorna
+/// `None` si el carácter no tiene representación en CP437
+pub fn from_char(character: char) -> Option<u8> {
+    if character.is_ascii() {
+        return Some(character as u8);
+    }
+
+    HIGH_HALF
+        .iter()
+        .position(|&c| c == character)
+        .map(|index| 0x80 + index as u8)
+}
+
+/// Convierte un carácter Unicode a CP437, usando '?' si no es representable
+pub fn from_char_lossy(character: char) -> u8 {
+    from_char(character).unwrap_or(REPLACEMENT)
+}
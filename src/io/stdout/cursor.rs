@@ -0,0 +1,145 @@
+//! Cursor de hardware de la VGA
+//!
+//! La posición y la forma del cursor parpadeante se controlan con los
+//! registros del CRTC, a los que se accede con un par índice/datos
+//! (0x3D4/0x3D5 en modo color, 0x3B4/0x3B5 en monocromo).
+
+use crate::io::ports::{read_port, write_port};
+use crate::io::stdout::{VGA_MONO_BUFFER, vga_base, width};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Puertos de índice del CRTC (el de datos es el siguiente)
+mod crtc_ports {
+    pub const COLOR_INDEX: u16 = 0x3D4;
+    pub const MONO_INDEX: u16 = 0x3B4;
+}
+
+/// Registros del CRTC usados por el cursor
+mod crtc_registers {
+    pub const CURSOR_START: u8 = 0x0A;
+    pub const CURSOR_END: u8 = 0x0B;
+    pub const CURSOR_LOCATION_HIGH: u8 = 0x0E;
+    pub const CURSOR_LOCATION_LOW: u8 = 0x0F;
+}
+
+/// Bit de Cursor Start que oculta el cursor
+const CURSOR_DISABLE: u8 = 0x20;
+
+/// Máscara de la scanline en Cursor Start / Cursor End
+const SCANLINE_MASK: u8 = 0x1F;
+
+/// Forma de bloque completo (scanlines 0 a 15)
+pub const CURSOR_BLOCK: (u8, u8) = (0, 15);
+
+/// Forma de subrayado (últimas dos scanlines de una celda de 16)
+pub const CURSOR_UNDERLINE: (u8, u8) = (14, 15);
+
+/// Puerto de índice del CRTC según el adaptador detectado por `init_vga`
+fn index_port() -> u16 {
+    if vga_base() == VGA_MONO_BUFFER {
+        crtc_ports::MONO_INDEX
+    } else {
+        crtc_ports::COLOR_INDEX
+    }
+}
+
+/// Lee un registro del CRTC
+pub(super) fn read_crtc(register: u8) -> u8 {
+    let index = index_port();
+    unsafe {
+        write_port(index, register);
+        read_port(index + 1)
+    }
+}
+
+/// Escribe un registro del CRTC
+fn write_crtc(register: u8, value: u8) {
+    let index = index_port();
+    unsafe {
+        write_port(index, register);
+        write_port(index + 1, value);
+    }
+}
+
+/// Cambia la forma del cursor
+///
+/// Conserva el estado oculto/visible y los bits altos de los registros.
+///
+/// # Argumentos
+/// * `start` - Primera scanline del cursor (0-31)
+/// * `end` - Última scanline del cursor (0-31)
+pub fn set_cursor_shape(start: u8, end: u8) {
+    let current_start = read_crtc(crtc_registers::CURSOR_START);
+    let current_end = read_crtc(crtc_registers::CURSOR_END);
+
+    write_crtc(
+        crtc_registers::CURSOR_START,
+        (current_start & !SCANLINE_MASK) | (start & SCANLINE_MASK),
+    );
+    write_crtc(
+        crtc_registers::CURSOR_END,
+        (current_end & !SCANLINE_MASK) | (end & SCANLINE_MASK),
+    );
+}
+
+/// Forma actual del cursor como `(start, end)`
+pub fn cursor_shape() -> (u8, u8) {
+    (
+        read_crtc(crtc_registers::CURSOR_START) & SCANLINE_MASK,
+        read_crtc(crtc_registers::CURSOR_END) & SCANLINE_MASK,
+    )
+}
+
+/// Oculta el cursor sin perder su forma
+pub fn hide_cursor() {
+    let start = read_crtc(crtc_registers::CURSOR_START);
+    write_crtc(crtc_registers::CURSOR_START, start | CURSOR_DISABLE);
+}
+
+/// Vuelve a mostrar el cursor con la forma que tenía
+pub fn show_cursor() {
+    let start = read_crtc(crtc_registers::CURSOR_START);
+    write_crtc(crtc_registers::CURSOR_START, start & !CURSOR_DISABLE);
+}
+
+/// Verifica si el cursor está visible
+pub fn is_cursor_visible() -> bool {
+    read_crtc(crtc_registers::CURSOR_START) & CURSOR_DISABLE == 0
+}
+
+/// Mueve el cursor de hardware a una celda
+pub fn set_cursor_position(x: u8, y: u8) {
+    let location = (y as usize * width() + x as usize) as u16;
+    write_crtc(crtc_registers::CURSOR_LOCATION_LOW, location as u8);
+    write_crtc(crtc_registers::CURSOR_LOCATION_HIGH, (location >> 8) as u8);
+}
+
+/// Si es falso, el cursor se oculta mientras se escribe con el writer
+/// global
+static CURSOR_BLINK: AtomicBool = AtomicBool::new(true);
+
+/// Habilita o deshabilita el parpadeo del cursor durante la salida
+///
+/// El parpadeo del cursor de hardware no se puede detener (es distinto
+/// del bit de parpadeo de los caracteres); lo único posible es ocultarlo
+/// con el bit 5 de Cursor Start. Con `enabled` en falso, `print!` y
+/// `println!` ocultan el cursor mientras escriben y lo vuelven a mostrar
+/// al terminar, así que solo se ve cuando la consola está inactiva. Por
+/// defecto está habilitado y la salida no toca el cursor.
+pub fn set_cursor_blink(enabled: bool) {
+    CURSOR_BLINK.store(enabled, Ordering::Relaxed);
+}
+
+/// Ejecuta `f` con el cursor oculto si el parpadeo está deshabilitado
+///
+/// Un cursor que ya estaba oculto sigue oculto al terminar.
+pub fn while_writing<R>(f: impl FnOnce() -> R) -> R {
+    if CURSOR_BLINK.load(Ordering::Relaxed) || !is_cursor_visible() {
+        return f();
+    }
+
+    hide_cursor();
+    let result = f();
+    show_cursor();
+    result
+}
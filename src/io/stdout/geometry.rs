@@ -0,0 +1,95 @@
+//! Tipos geométricos para posiciones y regiones de la pantalla
+//!
+//! Centralizan los cálculos de límites e índices que de otro modo se
+//! repiten con argumentos `x, y, w, h` sueltos.
+
+use crate::io::stdout::{height, width};
+
+/// Posición de una celda en la pantalla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point {
+    pub x: u8,
+    pub y: u8,
+}
+
+impl Point {
+    /// Crea una nueva posición
+    pub const fn new(x: u8, y: u8) -> Self {
+        Self { x, y }
+    }
+
+    /// Verifica si la posición está dentro de la pantalla
+    pub fn is_on_screen(&self) -> bool {
+        (self.x as usize) < width() && (self.y as usize) < height()
+    }
+
+    /// Índice lineal de la celda en el buffer VGA (`y * width() + x`)
+    ///
+    /// Es un índice de celdas; el offset en bytes es el doble.
+    pub fn index(&self) -> usize {
+        self.y as usize * width() + self.x as usize
+    }
+}
+
+/// Región rectangular de la pantalla
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u8,
+    pub y: u8,
+    pub w: u8,
+    pub h: u8,
+}
+
+impl Rect {
+    /// Crea una nueva región
+    pub const fn new(x: u8, y: u8, w: u8, h: u8) -> Self {
+        Self { x, y, w, h }
+    }
+
+    /// Esquina superior izquierda
+    pub const fn origin(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// Verifica si la posición está dentro de la región
+    pub fn contains(&self, point: Point) -> bool {
+        let end_x = self.x as usize + self.w as usize;
+        let end_y = self.y as usize + self.h as usize;
+
+        point.x >= self.x
+            && (point.x as usize) < end_x
+            && point.y >= self.y
+            && (point.y as usize) < end_y
+    }
+
+    /// Recorta la región a los límites de la pantalla
+    ///
+    /// Una región que empieza fuera de la pantalla queda con ancho o alto 0.
+    pub fn clamp_to_screen(&self) -> Self {
+        let end_x = (self.x as usize + self.w as usize).min(width());
+        let end_y = (self.y as usize + self.h as usize).min(height());
+
+        Self {
+            x: self.x,
+            y: self.y,
+            w: end_x.saturating_sub(self.x as usize) as u8,
+            h: end_y.saturating_sub(self.y as usize) as u8,
+        }
+    }
+
+    /// Recorre todas las posiciones de la región, fila por fila
+    ///
+    /// Las posiciones que no entran en coordenadas `u8` (una región que
+    /// empieza cerca de 255) se omiten en lugar de desbordar.
+    pub fn points(self) -> impl Iterator<Item = Point> {
+        let Self { x, y, w, h } = self;
+
+        (0..h)
+            .map_while(move |row| y.checked_add(row))
+            .flat_map(move |py| {
+                (0..w)
+                    .map_while(move |col| x.checked_add(col))
+                    .map(move |px| Point::new(px, py))
+            })
+    }
+}
@@ -0,0 +1,56 @@
+//! Volcado hexadecimal de memoria
+//!
+//! Muestra el contenido de una región de memoria en el formato clásico de
+//! 16 bytes por línea: offset, bytes en hexadecimal y columna ASCII.
+
+use crate::io::stdout::TextCursor;
+use core::fmt::Write;
+
+/// Bytes mostrados por línea
+const BYTES_PER_LINE: usize = 16;
+
+/// Escribe un volcado hexadecimal de `len` bytes empezando en `addr`
+///
+/// Cada línea tiene la forma:
+/// `00000010  48 6f 6c 61 ...  |Hola...|`
+/// Los bytes no imprimibles se muestran como '.' en la columna ASCII.
+/// Si `len` es 0 no se escribe nada.
+///
+/// # Seguridad
+/// Todo el rango `addr..addr + len` debe ser memoria legible. Los bytes
+/// se leen con `read_volatile`, así que se puede inspeccionar memoria de
+/// dispositivos, pero leer ciertos registros puede tener efectos
+/// secundarios.
+pub unsafe fn hexdump(addr: *const u8, len: usize, cursor: &mut TextCursor) {
+    for line_start in (0..len).step_by(BYTES_PER_LINE) {
+        let line_len = (len - line_start).min(BYTES_PER_LINE);
+        let mut line = [0u8; BYTES_PER_LINE];
+
+        for (offset, byte) in line[..line_len].iter_mut().enumerate() {
+            *byte = unsafe { core::ptr::read_volatile(addr.add(line_start + offset)) };
+        }
+
+        let _ = write!(cursor, "{:08x}  ", line_start);
+
+        // Bytes en hexadecimal, completando con espacios la última línea
+        for (index, byte) in line.iter().enumerate() {
+            if index < line_len {
+                let _ = write!(cursor, "{:02x} ", byte);
+            } else {
+                cursor.write_string(b"   ");
+            }
+        }
+
+        // Columna ASCII
+        cursor.write_string(b" |");
+        for &byte in &line[..line_len] {
+            let shown = if byte.is_ascii_graphic() || byte == b' ' {
+                byte
+            } else {
+                b'.'
+            };
+            cursor.write_char(shown);
+        }
+        cursor.write_string(b"|\n");
+    }
+}
@@ -0,0 +1,195 @@
+//! Distribución de texto en la pantalla
+//!
+//! `TextLayout` es la única implementación de saltos de línea, tabs y
+//! wrapping. `SysPrintableChar::new_string`, `chars_from_string`,
+//! `measure_string` y `TextCursor` la usan, así que el mismo texto queda
+//! en las mismas celdas sin importar cuál de ellos lo escriba.
+//!
+//! Reglas:
+//! * `\n` pasa a la fila siguiente, en la columna del margen
+//! * `\r` vuelve a la columna del margen en la fila actual
+//! * `\t` rellena con espacios hasta la próxima parada de `TAB_WIDTH`,
+//!   contando desde la columna 0 de la pantalla
+//! * el wrapping es perezoso: la fila cambia recién cuando se escribe un
+//!   carácter más allá del borde derecho
+//! * al pasar de la última fila se consulta a `LayoutSink::overflow`
+
+use crate::io::stdout::TAB_WIDTH;
+
+/// Destino de los caracteres distribuidos por `TextLayout`
+pub trait LayoutSink {
+    /// Recibe un carácter y su posición
+    ///
+    /// # Retorna
+    /// `false` para detener la distribución (por ejemplo, buffer lleno)
+    fn put(&mut self, character: u8, x: u8, y: u8) -> bool;
+
+    /// Se llama cuando un salto de línea pasa de la última fila
+    ///
+    /// # Retorna
+    /// La fila donde continuar, o `None` para terminar la distribución
+    fn overflow(&mut self) -> Option<u8> {
+        None
+    }
+}
+
+/// Estado de la distribución de texto
+#[derive(Debug, Clone, Copy)]
+pub struct TextLayout {
+    x: u8,
+    y: u8,
+    /// Columna a la que vuelven `\n`, `\r` y el wrapping
+    margin: u8,
+    /// Ancho de la pantalla destino
+    width: usize,
+    /// Alto de la pantalla destino
+    height: usize,
+    /// La distribución terminó (pasó de la última fila o las coordenadas
+    /// ya no entran en un `u8`)
+    finished: bool,
+}
+
+impl TextLayout {
+    /// Crea una distribución que empieza en `(x, y)`
+    ///
+    /// # Argumentos
+    /// * `x` - Columna inicial
+    /// * `y` - Fila inicial
+    /// * `margin` - Columna donde empiezan las líneas siguientes
+    /// * `size` - Dimensiones (ancho, alto) de la pantalla destino
+    pub const fn new(x: u8, y: u8, margin: u8, (width, height): (usize, usize)) -> Self {
+        Self {
+            x,
+            y,
+            margin,
+            width,
+            height,
+            finished: false,
+        }
+    }
+
+    /// Posición actual como `(x, y)`
+    ///
+    /// Después de escribir en la última columna `x` es igual al ancho:
+    /// el salto se hace recién con el próximo carácter.
+    pub const fn position(&self) -> (u8, u8) {
+        (self.x, self.y)
+    }
+
+    /// Verifica si la distribución pasó de la última fila
+    pub fn is_out_of_bounds(&self) -> bool {
+        self.finished || self.y as usize >= self.height
+    }
+
+    fn is_past_right_edge(&self) -> bool {
+        self.x as usize >= self.width
+    }
+
+    /// Procesa un byte de texto
+    ///
+    /// Los caracteres de control distintos de `\n`, `\r` y `\t` se
+    /// ignoran. Si `extended` es verdadero, los bytes 0x80-0xFF se tratan
+    /// como caracteres CP437 imprimibles.
+    ///
+    /// # Retorna
+    /// `false` si la distribución terminó (fuera de la pantalla o el
+    /// destino pidió detenerse)
+    pub fn feed(&mut self, byte: u8, extended: bool, sink: &mut impl LayoutSink) -> bool {
+        if self.is_out_of_bounds() {
+            return false;
+        }
+
+        match byte {
+            b'\n' => self.new_line(sink),
+            b'\r' => {
+                self.x = self.margin;
+                true
+            }
+            b'\t' => self.tab(sink),
+            printable_char
+                if printable_char.is_ascii_graphic()
+                    || printable_char == b' '
+                    || (extended && printable_char >= 0x80) =>
+            {
+                self.place(printable_char, sink)
+            }
+            _ => true, // Ignorar caracteres no imprimibles
+        }
+    }
+
+    /// Escribe un carácter tal cual, sin interpretar caracteres de control
+    ///
+    /// Si la posición quedó más allá del borde derecho, primero pasa a la
+    /// fila siguiente.
+    ///
+    /// # Retorna
+    /// `false` si la distribución terminó
+    pub fn place(&mut self, character: u8, sink: &mut impl LayoutSink) -> bool {
+        if self.is_past_right_edge() {
+            if !self.new_line(sink) {
+                return false;
+            }
+            // Un margen fuera de la pantalla no deja lugar para ningún
+            // carácter; sin esto el texto quedaría en columnas inválidas
+            if self.is_past_right_edge() {
+                self.finish();
+                return false;
+            }
+        }
+        if self.is_out_of_bounds() || !sink.put(character, self.x, self.y) {
+            return false;
+        }
+
+        // Con un ancho de 255 columnas `x` no puede avanzar más sin dar la
+        // vuelta a 0
+        match self.x.checked_add(1) {
+            Some(x) => self.x = x,
+            None => self.finish(),
+        }
+        true
+    }
+
+    /// Termina la distribución: los bytes siguientes se ignoran
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Rellena con espacios hasta la próxima parada de tabulación
+    fn tab(&mut self, sink: &mut impl LayoutSink) -> bool {
+        if self.is_past_right_edge() && !self.new_line(sink) {
+            return false;
+        }
+
+        let stop = ((self.x / TAB_WIDTH) as usize + 1) * TAB_WIDTH as usize;
+        while (self.x as usize) < stop.min(self.width) {
+            if !self.place(b' ', sink) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Pasa al inicio de la fila siguiente
+    fn new_line(&mut self, sink: &mut impl LayoutSink) -> bool {
+        self.x = self.margin;
+
+        if (self.y as usize) + 1 < self.height {
+            match self.y.checked_add(1) {
+                Some(y) => self.y = y,
+                None => self.finish(),
+            }
+            return !self.finished;
+        }
+
+        match sink.overflow() {
+            Some(row) => {
+                self.y = row;
+                true
+            }
+            None => {
+                self.finish();
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Texto que se desplaza horizontalmente en una fila
+//!
+//! Pensado para mensajes de estado más largos que el ancho de la pantalla:
+//! cada `advance` corre la ventana visible una columna y `render` dibuja
+//! la parte que entra, volviendo al inicio del texto al llegar al final.
+
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{fill_horizontal, try_write_char_at, width};
+use heapless::Vec;
+
+/// Espacios entre el final del texto y su repetición
+const MARQUEE_GAP: usize = 3;
+
+/// Texto de hasta `N` bytes que se desplaza en una fila
+pub struct Marquee<const N: usize> {
+    text: Vec<u8, N>,
+    /// Posición del texto que se muestra en la columna 0
+    offset: usize,
+}
+
+impl<const N: usize> Marquee<N> {
+    /// Crea un marquee con el texto indicado (se trunca a `N` bytes)
+    pub fn new(text: &[u8]) -> Self {
+        let mut marquee = Self {
+            text: Vec::new(),
+            offset: 0,
+        };
+        marquee.set_text(text);
+        marquee
+    }
+
+    /// Reemplaza el texto y vuelve al inicio
+    pub fn set_text(&mut self, text: &[u8]) {
+        self.text.clear();
+        let _ = self.text.extend_from_slice(&text[..text.len().min(N)]);
+        self.offset = 0;
+    }
+
+    /// Verifica si el texto es más largo que la pantalla
+    fn scrolls(&self) -> bool {
+        self.text.len() > width()
+    }
+
+    /// Largo de un ciclo completo: el texto más la separación
+    fn cycle_len(&self) -> usize {
+        self.text.len() + MARQUEE_GAP
+    }
+
+    /// Corre la ventana visible una columna a la izquierda
+    ///
+    /// No hace nada si el texto entra completo en la pantalla.
+    pub fn advance(&mut self) {
+        if self.scrolls() {
+            self.offset = (self.offset + 1) % self.cycle_len();
+        }
+    }
+
+    /// Dibuja la parte visible del texto en la fila `y`
+    ///
+    /// La fila se limpia antes de dibujar, así que un texto corto no deja
+    /// restos del anterior.
+    pub fn render(&self, y: u8, color: u8) {
+        fill_horizontal(b' ', color, 0, y, width() as u8);
+
+        for col in 0..width().min(self.text.len()) {
+            let index = if self.scrolls() {
+                (self.offset + col) % self.cycle_len()
+            } else {
+                col
+            };
+
+            if let Some(&character) = self.text.get(index) {
+                try_write_char_at(SysPrintableChar::new(character, color, col as u8, y));
+            }
+        }
+    }
+}
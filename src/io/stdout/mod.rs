@@ -3,23 +3,476 @@
 //! Proporciona funcionalidades para escribir texto en el buffer VGA
 //! de forma segura y eficiente.
 
+use crate::io::ports::read_port;
+use crate::io::stdout::colors::LIGHT_GRAY;
+use crate::io::stdout::layout::{LayoutSink, TextLayout};
 use crate::io::stdout::structs::SysPrintableChar;
+use crate::sync::{SpinLock, without_interrupts};
+use core::fmt;
+use core::sync::atomic::{AtomicPtr, AtomicU8, AtomicUsize, Ordering};
 use heapless::Vec;
 
 // Submódulos
+pub mod backend;
+pub mod banner;
 pub mod colors;
+pub mod cp437;
+pub mod cursor;
+pub mod geometry;
+pub mod hexdump;
+pub mod layout;
+pub mod marquee;
+pub mod pattern;
+pub mod prompt;
+pub mod region;
+pub mod screen;
+pub mod scrollback;
+pub mod status;
 pub mod structs;
+pub mod table;
+pub mod theme;
+pub mod vt;
+
+pub use backend::{HardwareVga, VgaBackend};
+pub use banner::draw_banner;
+pub use cursor::{cursor_shape, set_cursor_blink, set_cursor_position, set_cursor_shape};
+pub use geometry::{Point, Rect};
+pub use hexdump::hexdump;
+pub use marquee::Marquee;
+pub use pattern::draw_test_pattern;
+pub use prompt::countdown_prompt;
+pub use region::RegionWriter;
+pub use screen::Screen;
+pub use scrollback::reflow;
+pub use status::{StatusPosition, set_status_line, set_status_line_themed};
+pub use structs::measure_string;
+pub use table::Table;
+pub use theme::{Theme, set_theme};
+pub use vt::active_vt;
 
 /// Constantes del buffer VGA
 pub const VGA_BUFFER: *mut u8 = 0xb8000 as *mut u8;
+pub const VGA_MONO_BUFFER: *mut u8 = 0xb0000 as *mut u8;
 pub const VGA_WIDTH: usize = 80;
 pub const VGA_HEIGHT: usize = 25;
-pub const VGA_BUFFER_SIZE: usize = VGA_WIDTH * VGA_HEIGHT * 2;
+
+/// Celdas que caben en la memoria de modo texto (32 KiB desde 0xb8000)
+pub const VGA_MAX_CELLS: usize = 0x8000 / 2;
+
+/// Distancia entre paradas de tabulación de `TextCursor`
+pub const TAB_WIDTH: u8 = 4;
+
+/// Dimensiones actuales del modo texto
+///
+/// Por defecto son las del modo 80x25 (`VGA_WIDTH` x `VGA_HEIGHT`). Todas
+/// las verificaciones de límites y el wrapping usan `width()` y `height()`
+/// en lugar de las constantes, para poder cambiar de modo en tiempo de
+/// ejecución.
+static SCREEN_WIDTH: AtomicUsize = AtomicUsize::new(VGA_WIDTH);
+static SCREEN_HEIGHT: AtomicUsize = AtomicUsize::new(VGA_HEIGHT);
+
+/// Ancho actual de la pantalla en columnas
+#[inline]
+pub fn width() -> usize {
+    SCREEN_WIDTH.load(Ordering::Relaxed)
+}
+
+/// Alto actual de la pantalla en filas
+#[inline]
+pub fn height() -> usize {
+    SCREEN_HEIGHT.load(Ordering::Relaxed)
+}
+
+/// Cambia las dimensiones lógicas de la pantalla
+///
+/// Solo actualiza la geometría usada por las funciones de salida; el
+/// modo de video debe programarse por separado.
+///
+/// # Retorna
+/// `false` si las dimensiones no caben en la memoria de modo texto o no
+/// son representables como coordenadas `u8`
+pub fn set_dimensions(new_width: usize, new_height: usize) -> bool {
+    let fits = new_width > 0
+        && new_height > 0
+        && new_width <= u8::MAX as usize
+        && new_height <= u8::MAX as usize
+        && new_width * new_height <= VGA_MAX_CELLS;
+
+    if fits {
+        SCREEN_WIDTH.store(new_width, Ordering::Relaxed);
+        SCREEN_HEIGHT.store(new_height, Ordering::Relaxed);
+    }
+    fits
+}
+
+/// Dirección base del buffer de texto en uso
+///
+/// Por defecto es la del modo color (`VGA_BUFFER`); `init_vga` la cambia a
+/// `VGA_MONO_BUFFER` si detecta un adaptador monocromo.
+static VGA_BASE: AtomicPtr<u8> = AtomicPtr::new(VGA_BUFFER);
+
+/// Registro Miscellaneous Output (lectura)
+const MISC_OUTPUT_READ: u16 = 0x3CC;
+
+/// Bit del registro Miscellaneous Output que indica direcciones de color
+const MISC_OUTPUT_COLOR: u8 = 0x01;
+
+/// Detecta la dirección del buffer de texto según el adaptador
+///
+/// Lee el bit 0 del registro Miscellaneous Output (puerto 0x3CC): en 1 el
+/// adaptador usa las direcciones de color (0xb8000), en 0 las monocromas
+/// (0xb0000). Si el registro devuelve 0xFF (bus flotante, sin VGA) la
+/// detección se considera ambigua y se mantiene 0xb8000.
+///
+/// Después lee del CRTC las dimensiones del modo actual (ver
+/// `set_dimensions`).
+///
+/// La dirección monocroma debe estar mapeada en la tabla de páginas para
+/// poder escribir en ella.
+pub fn init_vga() {
+    let misc = unsafe { read_port(MISC_OUTPUT_READ) };
+
+    let base = if misc != 0xFF && misc & MISC_OUTPUT_COLOR == 0 {
+        VGA_MONO_BUFFER
+    } else {
+        VGA_BUFFER
+    };
+    VGA_BASE.store(base, Ordering::Relaxed);
+
+    // Si el BIOS dejó otro modo de texto (por ejemplo 80x50), se usa su
+    // geometría; con valores inválidos quedan las dimensiones por defecto
+    if let Some((columns, rows)) = detect_dimensions() {
+        set_dimensions(columns, rows);
+    }
+}
+
+/// Registros del CRTC con la geometría del modo de texto
+mod geometry_registers {
+    pub const HORIZONTAL_DISPLAY_END: u8 = 0x01;
+    pub const OVERFLOW: u8 = 0x07;
+    pub const MAXIMUM_SCAN_LINE: u8 = 0x09;
+    pub const VERTICAL_DISPLAY_END: u8 = 0x12;
+}
+
+/// Bit de Maximum Scan Line que duplica cada scanline
+const SCAN_DOUBLING: u8 = 0x80;
+
+/// Bits de Maximum Scan Line con el alto de un carácter menos uno
+const CHARACTER_HEIGHT_MASK: u8 = 0x1F;
+
+/// Lee del CRTC las dimensiones del modo de texto programado
+///
+/// Las columnas salen de Horizontal Display End y las filas de dividir
+/// las scanlines visibles (Vertical Display End, con sus bits 8 y 9 en
+/// el registro Overflow) por el alto de un carácter (Maximum Scan Line).
+///
+/// # Retorna
+/// `(columnas, filas)`, o `None` si no se puede armar ninguna fila
+fn detect_dimensions() -> Option<(usize, usize)> {
+    let columns = cursor::read_crtc(geometry_registers::HORIZONTAL_DISPLAY_END) as usize + 1;
+
+    let overflow = cursor::read_crtc(geometry_registers::OVERFLOW) as usize;
+    let display_end = cursor::read_crtc(geometry_registers::VERTICAL_DISPLAY_END) as usize
+        | ((overflow >> 1) & 1) << 8
+        | ((overflow >> 6) & 1) << 9;
+    let max_scan_line = cursor::read_crtc(geometry_registers::MAXIMUM_SCAN_LINE);
+
+    let mut scanlines = display_end + 1;
+    if max_scan_line & SCAN_DOUBLING != 0 {
+        scanlines /= 2;
+    }
+    let rows = scanlines / ((max_scan_line & CHARACTER_HEIGHT_MASK) as usize + 1);
+
+    (rows > 0).then_some((columns, rows))
+}
+
+/// Puntero al inicio del buffer de texto en uso
+#[inline]
+pub fn vga_base() -> *mut u8 {
+    VGA_BASE.load(Ordering::Relaxed)
+}
+
+/// Color usado por las funciones `*_default`
+static DEFAULT_COLOR: AtomicU8 = AtomicU8::new(LIGHT_GRAY);
+
+/// Cambia el color por defecto de la salida
+///
+/// Permite aplicar un tema a toda la consola (por ejemplo verde sobre
+/// negro) sin modificar cada llamada. Las funciones que reciben un color
+/// explícito no se ven afectadas.
+pub fn set_default_color(color: u8) {
+    DEFAULT_COLOR.store(color, Ordering::Relaxed);
+}
+
+/// Obtiene el color por defecto de la salida
+pub fn default_color() -> u8 {
+    DEFAULT_COLOR.load(Ordering::Relaxed)
+}
+
+/// Combina un carácter y su color en una celda del buffer VGA
+#[inline(always)]
+pub const fn make_cell(character: u8, color: u8) -> u16 {
+    (color as u16) << 8 | character as u16
+}
+
+/// Lee una celda completa (carácter + color) del buffer VGA
+///
+/// El byte bajo contiene el carácter y el byte alto el color, igual que
+/// su representación en memoria.
+///
+/// # Retorna
+/// `None` si la posición está fuera de la pantalla
+pub fn read_cell(x: u8, y: u8) -> Option<u16> {
+    let point = Point::new(x, y);
+    if !point.is_on_screen() {
+        return None;
+    }
+
+    let index = point.index();
+    unsafe {
+        Some(core::ptr::read_volatile(
+            (vga_base() as *const u16).add(index),
+        ))
+    }
+}
+
+/// Escribe una celda completa (carácter + color) en el buffer VGA
+///
+/// Las posiciones fuera de la pantalla son ignoradas silenciosamente.
+pub fn write_cell(x: u8, y: u8, cell: u16) {
+    let point = Point::new(x, y);
+    if !point.is_on_screen() {
+        return;
+    }
+
+    let index = point.index();
+    unsafe {
+        core::ptr::write_volatile((vga_base() as *mut u16).add(index), cell);
+    }
+}
+
+/// Escribe una celda solo si su contenido actual es distinto
+///
+/// Lee la celda antes de escribirla, así que conviene en pantallas casi
+/// estáticas que se redibujan seguido; si se sabe que la celda cambió,
+/// `write_cell` evita la lectura.
+///
+/// # Retorna
+/// `true` si la celda se escribió
+pub fn write_cell_if_changed(x: u8, y: u8, cell: u16) -> bool {
+    match read_cell(x, y) {
+        Some(current) if current != cell => {
+            write_cell(x, y, cell);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Versión de `write_char_at` que omite la escritura si la celda ya
+/// tiene el mismo carácter y color
+///
+/// # Retorna
+/// `true` si la celda se escribió
+pub fn write_char_at_if_changed(syschar: SysPrintableChar) -> bool {
+    write_cell_if_changed(
+        syschar.x,
+        syschar.y,
+        make_cell(syschar.character, syschar.color),
+    )
+}
+
+/// Buffer de texto completo como slice de celdas, solo lectura
+///
+/// El slice cubre las `width() * height()` celdas visibles (80x25 en el
+/// modo por defecto) a partir de `vga_base()`.
+///
+/// # Seguridad
+/// Mientras el slice exista nadie debe escribir en la pantalla: las
+/// funciones de este módulo, `print!` o una ISR modificarían memoria que
+/// el compilador asume que no cambia. Las lecturas no son volátiles.
+pub unsafe fn buffer() -> &'static [u16] {
+    unsafe { core::slice::from_raw_parts(vga_base() as *const u16, width() * height()) }
+}
+
+/// Buffer de texto completo como slice mutable de celdas
+///
+/// Vía de escape para algoritmos que el módulo no ofrece: permite operar
+/// sobre todas las celdas visibles como un `[u16]` común. Cada celda usa
+/// el formato de `make_cell`.
+///
+/// # Seguridad
+/// El llamador debe garantizar acceso exclusivo a la pantalla mientras el
+/// slice exista: solo un slice a la vez, ninguna otra escritura (funciones
+/// de este módulo, `print!`, el writer global) y ninguna ISR que escriba
+/// en pantalla, por ejemplo manteniendo las interrupciones deshabilitadas.
+/// No debe guardarse más allá de ese bloque ni sobrevivir a un cambio de
+/// dimensiones o de `vga_base()`.
+pub unsafe fn buffer_mut() -> &'static mut [u16] {
+    unsafe { core::slice::from_raw_parts_mut(vga_base() as *mut u16, width() * height()) }
+}
+
+/// Guarda una copia de toda la pantalla
+///
+/// Útil para diálogos modales: se guarda la pantalla antes de dibujar
+/// y se restaura con `restore_screen` al cerrarlos. El buffer alcanza para
+/// cualquier modo de texto; se copian las `width() * height()` celdas del
+/// modo actual.
+pub fn save_screen(into: &mut [u16; VGA_MAX_CELLS]) {
+    let cells = width() * height();
+    for (index, cell) in into.iter_mut().take(cells).enumerate() {
+        unsafe {
+            *cell = core::ptr::read_volatile((vga_base() as *const u16).add(index));
+        }
+    }
+}
+
+/// Restaura la pantalla completa desde una copia hecha con `save_screen`
+///
+/// Igual que `save_screen`, solo se escriben las celdas del modo actual.
+pub fn restore_screen(from: &[u16; VGA_MAX_CELLS]) {
+    let cells = width() * height();
+    for (index, &cell) in from.iter().take(cells).enumerate() {
+        unsafe {
+            core::ptr::write_volatile((vga_base() as *mut u16).add(index), cell);
+        }
+    }
+}
+
+/// Guarda una región rectangular de la pantalla
+///
+/// La región se recorta a los límites de la pantalla y se copia fila por
+/// fila en `into`. Si el buffer es más pequeño que la región, se copian
+/// solo las celdas que caben.
+///
+/// # Argumentos
+/// * `x`, `y` - Esquina superior izquierda de la región
+/// * `w`, `h` - Ancho y alto de la región
+/// * `into` - Buffer de destino
+///
+/// # Retorna
+/// La cantidad de celdas copiadas
+pub fn save_region(x: u8, y: u8, w: u8, h: u8, into: &mut [u16]) -> usize {
+    let mut copied = 0;
+
+    for (row, col) in region_cells(x, y, w, h) {
+        if copied >= into.len() {
+            break;
+        }
+        if let Some(cell) = read_cell(col, row) {
+            into[copied] = cell;
+            copied += 1;
+        }
+    }
+
+    copied
+}
+
+/// Restaura una región guardada con `save_region`
+///
+/// Debe llamarse con las mismas coordenadas y dimensiones usadas al guardar.
+///
+/// # Retorna
+/// La cantidad de celdas restauradas
+pub fn restore_region(x: u8, y: u8, w: u8, h: u8, from: &[u16]) -> usize {
+    let mut restored = 0;
+
+    for (row, col) in region_cells(x, y, w, h) {
+        if restored >= from.len() {
+            break;
+        }
+        write_cell(col, row, from[restored]);
+        restored += 1;
+    }
+
+    restored
+}
+
+/// Copia un rectángulo de celdas a la pantalla
+///
+/// `cells` se lee fila por fila con `src_w` celdas por fila. Las celdas
+/// que caen fuera de la pantalla se omiten, así que el rectángulo puede
+/// quedar parcialmente visible en los bordes. Si `cells` tiene menos de
+/// `src_w * src_h` celdas, se copian solo las que hay.
+///
+/// # Argumentos
+/// * `cells` - Celdas de origen (carácter + color)
+/// * `src_w`, `src_h` - Ancho y alto del rectángulo de origen
+/// * `dst_x`, `dst_y` - Posición de la esquina superior izquierda en pantalla
+pub fn blit(cells: &[u16], src_w: u8, src_h: u8, dst_x: u8, dst_y: u8) {
+    blit_cells(cells, src_w, src_h, dst_x, dst_y, None);
+}
+
+/// Igual que `blit`, pero omite las celdas iguales a `transparent`
+///
+/// Permite dibujar sprites no rectangulares: las celdas con el valor
+/// centinela dejan ver lo que ya había en pantalla.
+pub fn blit_transparent(
+    cells: &[u16],
+    src_w: u8,
+    src_h: u8,
+    dst_x: u8,
+    dst_y: u8,
+    transparent: u16,
+) {
+    blit_cells(cells, src_w, src_h, dst_x, dst_y, Some(transparent));
+}
+
+fn blit_cells(cells: &[u16], src_w: u8, src_h: u8, dst_x: u8, dst_y: u8, transparent: Option<u16>) {
+    for row in 0..src_h {
+        let Some(y) = dst_y.checked_add(row) else {
+            break;
+        };
+
+        for col in 0..src_w {
+            let Some(&cell) = cells.get(row as usize * src_w as usize + col as usize) else {
+                return;
+            };
+            if Some(cell) == transparent {
+                continue;
+            }
+            // Fuera de la pantalla: `write_cell` la ignora
+            if let Some(x) = dst_x.checked_add(col) {
+                write_cell(x, y, cell);
+            }
+        }
+    }
+}
+
+/// Invierte los colores de una región (video inverso)
+///
+/// Intercambia el foreground y el background de cada celda, conservando
+/// el carácter. Llamarla dos veces con la misma región restaura los
+/// colores originales, lo que sirve para resaltar la fila seleccionada de
+/// un menú.
+///
+/// Con el parpadeo habilitado (ver `colors::set_blink_enabled`), un
+/// foreground brillante pasa al bit alto del fondo y la celda parpadea;
+/// deshabilitar el parpadeo para obtener un fondo brillante fijo.
+pub fn invert_region(x: u8, y: u8, w: u8, h: u8) {
+    for (row, col) in region_cells(x, y, w, h) {
+        if let Some(cell) = read_cell(col, row) {
+            let [character, color] = cell.to_le_bytes();
+            let inverted =
+                colors::make_color(colors::get_background(color), colors::get_foreground(color));
+            write_cell(col, row, make_cell(character, inverted));
+        }
+    }
+}
+
+/// Recorre las posiciones (fila, columna) de una región recortada a la pantalla
+fn region_cells(x: u8, y: u8, w: u8, h: u8) -> impl Iterator<Item = (u8, u8)> {
+    Rect::new(x, y, w, h)
+        .clamp_to_screen()
+        .points()
+        .map(|point| (point.y, point.x))
+}
 
 /// Escribe un carácter en una posición específica del buffer VGA
 ///
 /// Esta función es segura siempre que el carácter esté dentro de los límites
-/// de la pantalla. Los caracteres fuera de límites son ignorados silenciosamente.
+/// de la pantalla. Los caracteres fuera de límites son ignorados silenciosamente;
+/// usar `try_write_char_at` para saber si la escritura ocurrió.
 ///
 /// # Argumentos
 /// * `syschar` - El carácter a escribir con su posición y color
@@ -28,20 +481,43 @@ pub const VGA_BUFFER_SIZE: usize = VGA_WIDTH * VGA_HEIGHT * 2;
 /// Esta función usa punteros raw para acceder al buffer VGA, pero incluye
 /// verificaciones de límites para prevenir escrituras fuera del buffer.
 pub fn write_char_at(syschar: structs::SysPrintableChar) {
+    try_write_char_at(syschar);
+}
+
+/// Escribe un carácter e indica si quedó dentro de la pantalla
+///
+/// Igual que `write_char_at`, pero permite al código de layout detectar
+/// recortes (por ejemplo para desplazar la pantalla o truncar texto).
+///
+/// # Retorna
+/// `true` si el carácter se escribió, `false` si estaba fuera de límites
+pub fn try_write_char_at(syschar: structs::SysPrintableChar) -> bool {
     // Verificar que el carácter esté dentro de los límites
     if !syschar.is_valid() {
-        return; // Ignorar silenciosamente caracteres fuera de límites
+        return false;
     }
 
-    let index = (syschar.y as usize * VGA_WIDTH + syschar.x as usize) * 2;
+    let index = (syschar.y as usize * width() + syschar.x as usize) * 2;
+    let buffer_size = width() * height() * 2;
 
-    // Verificación adicional del índice por seguridad
-    if index + 1 < VGA_BUFFER_SIZE {
-        unsafe {
-            *VGA_BUFFER.add(index) = syschar.character;
-            *VGA_BUFFER.add(index + 1) = syschar.color;
-        }
+    // Verificación adicional del índice por seguridad: la celda ocupa dos
+    // bytes (carácter en `index`, color en `index + 1`), así que ambos deben
+    // caber en el buffer. La última celda (79, 24 en 80x25) usa los bytes
+    // `buffer_size - 2` y `buffer_size - 1`, y pasa esta verificación.
+    if index + 2 > buffer_size {
+        return false;
+    }
+
+    unsafe {
+        *vga_base().add(index) = syschar.character;
+        *vga_base().add(index + 1) = syschar.color;
     }
+    true
+}
+
+/// Escribe un carácter en una posición dada como `Point`
+pub fn write_char_at_point(point: Point, character: u8, color: u8) {
+    write_char_at(SysPrintableChar::new(character, color, point.x, point.y));
 }
 
 /// Escribe múltiples caracteres desde un buffer
@@ -55,7 +531,18 @@ pub fn write_char_at(syschar: structs::SysPrintableChar) {
 /// # Tipo genérico
 /// * `N` - Tamaño máximo del vector (determinado en tiempo de compilación)
 pub fn write_buffer<const N: usize>(vec: Vec<SysPrintableChar, N>) {
-    for char in vec {
+    write_slice(&vec);
+}
+
+/// Escribe múltiples caracteres desde un slice
+///
+/// A diferencia de `write_buffer`, no consume el buffer, por lo que el
+/// mismo contenido puede dibujarse varias veces.
+///
+/// # Argumentos
+/// * `chars` - Caracteres a escribir
+pub fn write_slice(chars: &[SysPrintableChar]) {
+    for &char in chars {
         write_char_at(char);
     }
 }
@@ -64,19 +551,242 @@ pub fn write_buffer<const N: usize>(vec: Vec<SysPrintableChar, N>) {
 ///
 /// Llena toda la pantalla con espacios en blanco usando el color especificado.
 pub fn clear_screen(background_color: u8) {
-    use crate::io::stdout::colors::BLACK;
-
-    let clear_char = structs::SysPrintableChar::new(b' ', background_color, 0, 0);
+    HardwareVga.clear(background_color);
+}
 
-    for y in 0..VGA_HEIGHT {
-        for x in 0..VGA_WIDTH {
-            let char_at_pos =
-                structs::SysPrintableChar::new(b' ', background_color, x as u8, y as u8);
-            write_char_at(char_at_pos);
+/// Borra todos los caracteres de la pantalla conservando sus colores
+///
+/// A diferencia de `clear_screen`, cada celda mantiene su byte de color y
+/// solo el carácter pasa a ser un espacio, así que un fondo con tema o
+/// colores por zona siguen visibles.
+pub fn clear_chars() {
+    // `buffer_mut` pide acceso exclusivo: ninguna ISR puede escribir
+    without_interrupts(|| {
+        let cells = unsafe { buffer_mut() };
+        for cell in cells {
+            *cell = *cell & 0xFF00 | b' ' as u16;
         }
+    });
+}
+
+/// Limpia una región rectangular con espacios del color indicado
+///
+/// La región se recorta a los límites de la pantalla.
+pub fn clear_region(rect: Rect, color: u8) {
+    let blank = make_cell(b' ', color);
+    for point in rect.clamp_to_screen().points() {
+        write_cell(point.x, point.y, blank);
+    }
+}
+
+/// Escribe `count` copias de un carácter hacia la derecha desde `(x, y)`
+///
+/// La corrida se recorta una sola vez al borde derecho de la pantalla; si
+/// la fila está fuera de la pantalla no se escribe nada. Ver
+/// `VgaBackend::fill_horizontal`.
+pub fn fill_horizontal(character: u8, color: u8, x: u8, y: u8, count: u8) {
+    HardwareVga.fill_horizontal(character, color, x, y, count);
+}
+
+/// Escribe `count` copias de un carácter hacia abajo desde `(x, y)`
+///
+/// Igual que `fill_horizontal`, pero la corrida se recorta al borde
+/// inferior de la pantalla.
+pub fn fill_vertical(character: u8, color: u8, x: u8, y: u8, count: u8) {
+    HardwareVga.fill_vertical(character, color, x, y, count);
+}
+
+/// Llena la pantalla con espacios cuyo fondo pasa de `top_color` a
+/// `bottom_color`
+///
+/// El índice de color del fondo se interpola fila por fila entre los dos
+/// extremos, así que con solo 16 colores el resultado son franjas de
+/// varias filas. El foreground de cada celda es el del color por defecto,
+/// para que el texto escrito encima se siga viendo igual. Los fondos
+/// 0x8-0xF solo se ven como tales con el parpadeo deshabilitado (ver
+/// `colors::set_blink_enabled`).
+///
+/// # Argumentos
+/// * `top_color` - Color de fondo de la primera fila (0x0-0xF)
+/// * `bottom_color` - Color de fondo de la última fila (0x0-0xF)
+pub fn fill_background_gradient(top_color: u8, bottom_color: u8) {
+    let text_color = default_color();
+    let top = (top_color & 0x0F) as usize;
+    let bottom = (bottom_color & 0x0F) as usize;
+    let last_row = height().saturating_sub(1).max(1);
+    let columns = width().min(u8::MAX as usize) as u8;
+
+    for row in 0..height().min(u8::MAX as usize) {
+        // Interpolación redondeada entre los dos índices
+        let background = if bottom >= top {
+            top + ((bottom - top) * row + last_row / 2) / last_row
+        } else {
+            top - ((top - bottom) * row + last_row / 2) / last_row
+        };
+        let color = colors::with_background(text_color, background as u8);
+        fill_horizontal(b' ', color, 0, row as u8, columns);
     }
 }
 
+/// Lleva una coordenada calculada con signo al rango de la pantalla
+///
+/// Los valores negativos pasan a 0 y los que superan el límite a
+/// `limit - 1`, de modo que una cuenta como `(80 - len) / 2` con un texto
+/// más largo que la pantalla no termina en una posición arbitraria.
+///
+/// # Argumentos
+/// * `value` - Coordenada calculada
+/// * `limit` - Ancho o alto de la pantalla (`width()` o `height()`)
+pub fn clamp_coordinate(value: i16, limit: usize) -> u8 {
+    let max = limit.saturating_sub(1).min(u8::MAX as usize) as i16;
+    value.clamp(0, max) as u8
+}
+
+/// Escribe una línea de texto centrada horizontalmente en la fila `y`
+///
+/// Un texto más ancho que la pantalla empieza en la columna 0 y se
+/// recorta en el borde derecho.
+pub fn write_centered(text: &[u8], color: u8, y: u8) {
+    write_centered_on(&mut HardwareVga, text, color, y);
+}
+
+/// Igual que `write_centered`, pero sobre cualquier backend
+pub fn write_centered_on(backend: &mut impl VgaBackend, text: &[u8], color: u8, y: u8) {
+    let width = backend.width();
+    let x = clamp_coordinate((width as i16 - text_width(text)) / 2, width);
+    write_line_clipped(backend, text, color, x, y);
+}
+
+/// Escribe una línea de texto alineada al borde derecho en la fila `y`
+///
+/// Igual que `write_centered`, un texto más ancho que la pantalla empieza
+/// en la columna 0 y se recorta.
+pub fn write_right_aligned(text: &[u8], color: u8, y: u8) {
+    write_right_aligned_on(&mut HardwareVga, text, color, y);
+}
+
+/// Igual que `write_right_aligned`, pero sobre cualquier backend
+pub fn write_right_aligned_on(backend: &mut impl VgaBackend, text: &[u8], color: u8, y: u8) {
+    let width = backend.width();
+    let x = clamp_coordinate(width as i16 - text_width(text), width);
+    write_line_clipped(backend, text, color, x, y);
+}
+
+/// Largo de `text` como coordenada con signo (satura en `i16::MAX`)
+fn text_width(text: &[u8]) -> i16 {
+    text.len().min(i16::MAX as usize) as i16
+}
+
+/// Escribe los bytes de `text` desde `(x, y)` sin pasar a la fila siguiente
+fn write_line_clipped(backend: &mut impl VgaBackend, text: &[u8], color: u8, x: u8, y: u8) {
+    let columns = backend.width().saturating_sub(x as usize);
+    for (offset, &character) in text.iter().take(columns).enumerate() {
+        backend.put_char(SysPrintableChar::new(character, color, x + offset as u8, y));
+    }
+}
+
+/// Dibuja el borde de una caja con caracteres de línea simple
+///
+/// El interior no se modifica. Las partes del borde fuera de la pantalla
+/// se ignoran; una región de menos de 2x2 no dibuja nada.
+pub fn draw_box(rect: Rect, color: u8) {
+    HardwareVga.draw_box(rect, color);
+}
+
+/// Región de desplazamiento (filas `top..bottom`)
+///
+/// Un `bottom` de 0 indica que la región llega hasta la última fila, para
+/// que siga siendo válida si cambian las dimensiones de la pantalla.
+static SCROLL_TOP: AtomicUsize = AtomicUsize::new(0);
+static SCROLL_BOTTOM: AtomicUsize = AtomicUsize::new(0);
+
+/// Limita el desplazamiento a las filas `top..bottom`
+///
+/// Las filas fuera de la región quedan fijas cuando `scroll_up` desplaza
+/// el contenido, lo que permite reservar líneas de estado.
+///
+/// # Retorna
+/// `false` si la región está vacía o no cabe en la pantalla
+pub fn set_scroll_region(top: usize, bottom: usize) -> bool {
+    let valid = top < bottom && bottom <= height();
+
+    if valid {
+        SCROLL_TOP.store(top, Ordering::Relaxed);
+        SCROLL_BOTTOM.store(bottom, Ordering::Relaxed);
+    }
+    valid
+}
+
+/// Vuelve a desplazar la pantalla completa
+pub fn reset_scroll_region() {
+    SCROLL_TOP.store(0, Ordering::Relaxed);
+    SCROLL_BOTTOM.store(0, Ordering::Relaxed);
+}
+
+/// Región de desplazamiento actual como `(top, bottom)`, con `bottom`
+/// exclusivo
+pub fn scroll_region() -> (usize, usize) {
+    let bottom = match SCROLL_BOTTOM.load(Ordering::Relaxed) {
+        0 => height(),
+        bottom => bottom.min(height()),
+    };
+    (SCROLL_TOP.load(Ordering::Relaxed).min(bottom), bottom)
+}
+
+/// Desplaza el contenido de la región de desplazamiento hacia arriba
+///
+/// Las primeras `lines` líneas de la región se descartan y las últimas
+/// quedan en blanco con el color indicado. Sin región configurada se
+/// desplaza la pantalla completa.
+pub fn scroll_up(lines: usize, blank_color: u8) {
+    scroll_up_on(&mut HardwareVga, lines, blank_color);
+}
+
+/// Igual que `scroll_up`, pero sobre cualquier backend
+///
+/// La región de desplazamiento se recorta al alto del backend, así que
+/// sobre un `ArrayBackend` más chico que la pantalla se desplazan solo
+/// sus filas.
+pub fn scroll_up_on(backend: &mut impl VgaBackend, lines: usize, blank_color: u8) {
+    let (top, bottom) = scroll_region();
+    backend.scroll_rows_up(top, bottom, lines, blank_color);
+}
+
+/// Desplaza el contenido de la región de desplazamiento hacia abajo
+///
+/// Las últimas `lines` líneas de la región se descartan y las primeras
+/// quedan en blanco con el color indicado. Desplazar más líneas que el
+/// alto de la región la deja completamente en blanco.
+pub fn scroll_down(lines: usize, blank_color: u8) {
+    scroll_down_on(&mut HardwareVga, lines, blank_color);
+}
+
+/// Igual que `scroll_down`, pero sobre cualquier backend
+pub fn scroll_down_on(backend: &mut impl VgaBackend, lines: usize, blank_color: u8) {
+    let (top, bottom) = scroll_region();
+    backend.scroll_rows_down(top, bottom, lines, blank_color);
+}
+
+/// Garantiza que las escrituras previas al buffer de texto sean visibles
+///
+/// Emite una barrera de memoria completa (`mfence` en x86) para drenar
+/// los buffers de write-combining antes de continuar. En modo texto VGA
+/// la memoria de video no suele estar cacheada y las escrituras volátiles
+/// ya llegan en orden, pero deja de ser así si la región se mapea como
+/// write-combining o al cambiar de modo. Conviene llamarla:
+///
+/// * al terminar de volcar un back buffer (`Screen::present` lo hace)
+/// * antes de detener la CPU (panic, apagado) o reiniciar
+/// * antes de reprogramar el modo de video
+pub fn flush() {
+    core::sync::atomic::fence(Ordering::SeqCst);
+}
+
+/// Llena una fila completa con espacios del color indicado
+pub fn clear_line(y: u8, color: u8) {
+    clear_region(Rect::new(0, y, width() as u8, 1), color);
+}
+
 /// Escribe una línea de texto en una posición específica
 ///
 /// Función de conveniencia para escribir strings simples.
@@ -91,6 +801,157 @@ pub fn write_string_at(text: &[u8], color: u8, x: u8, y: u8) {
     write_buffer(chars);
 }
 
+/// Escribe una línea de texto usando el color por defecto
+///
+/// Igual que `write_string_at` con `default_color()`.
+pub fn write_string_at_default(text: &[u8], x: u8, y: u8) {
+    write_string_at(text, default_color(), x, y);
+}
+
+/// Escribe texto UTF-8 en una posición específica
+///
+/// Los caracteres no ASCII se convierten a CP437 (por ejemplo 'é', 'ñ',
+/// '°' o los caracteres de dibujo de cajas); los que no tienen equivalente
+/// se muestran como '?'.
+///
+/// # Argumentos
+/// * `text` - El texto a escribir
+/// * `color` - Color del texto
+/// * `x` - Posición X inicial
+/// * `y` - Posición Y inicial
+pub fn write_utf8(text: &str, color: u8, x: u8, y: u8) {
+    let chars = structs::SysPrintableChar::new_utf8_string(text, color, x, y);
+    write_buffer(chars);
+}
+
+/// Equivalente de `write_string_at` para `&str`
+///
+/// Evita pasar `text.as_bytes()`, que mostraría cada byte de un carácter
+/// no ASCII como un símbolo CP437 distinto. Los caracteres se convierten
+/// como en `write_utf8` y el wrapping es el mismo de `new_string`.
+pub fn write_str_at(text: &str, color: u8, x: u8, y: u8) {
+    write_utf8(text, color, x, y);
+}
+
+/// Escribe texto de a un carácter, con una pausa entre cada uno
+///
+/// El texto se escribe con un `TextCursor`, así que los saltos de línea y
+/// el wrapping son los de `TextCursor::write_string`. Con `delay_ms` en 0
+/// se escribe todo de una vez. Bloquea hasta terminar (usa
+/// `timer::sleep_ms`).
+///
+/// # Argumentos
+/// * `text` - El texto a escribir
+/// * `color` - Color del texto
+/// * `x` - Posición X inicial
+/// * `y` - Posición Y inicial
+/// * `delay_ms` - Milisegundos entre cada carácter
+pub fn type_text(text: &[u8], color: u8, x: u8, y: u8, delay_ms: u64) {
+    let mut cursor = TextCursor::new(x, y, color);
+
+    if delay_ms == 0 {
+        cursor.write_string(text);
+        return;
+    }
+
+    for byte in text.chunks(1) {
+        cursor.write_string(byte);
+        crate::timer::sleep_ms(delay_ms);
+    }
+}
+
+/// Destino de `TextLayout` que escribe en un backend
+struct BackendSink<'a, B: VgaBackend> {
+    backend: &'a mut B,
+    color: u8,
+}
+
+impl<B: VgaBackend> LayoutSink for BackendSink<'_, B> {
+    fn put(&mut self, character: u8, x: u8, y: u8) -> bool {
+        self.backend
+            .put_char(structs::SysPrintableChar::new(character, self.color, x, y));
+        true
+    }
+}
+
+/// Adaptador de `fmt::Write` que distribuye el texto desde una posición
+struct FmtAt<'a, B: VgaBackend> {
+    layout: TextLayout,
+    sink: BackendSink<'a, B>,
+}
+
+impl<B: VgaBackend> fmt::Write for FmtAt<'_, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars() {
+            if !self
+                .layout
+                .feed(cp437::from_char_lossy(character), true, &mut self.sink)
+            {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escribe texto formateado en una posición específica
+///
+/// El texto se formatea directo en el buffer VGA, sin buffers
+/// intermedios que puedan truncarlo. El wrapping es el de `write_utf8`:
+/// las líneas siguientes empiezan en la columna `x` y el texto que no
+/// entra debajo de la última fila se descarta.
+///
+/// # Argumentos
+/// * `args` - Texto a formatear, normalmente de `format_args!`
+/// * `color` - Color del texto
+/// * `x` - Posición X inicial
+/// * `y` - Posición Y inicial
+pub fn write_fmt_at(args: fmt::Arguments, color: u8, x: u8, y: u8) {
+    write_fmt_on(&mut HardwareVga, args, color, x, y);
+}
+
+/// Igual que `write_fmt_at`, pero sobre cualquier backend
+///
+/// El wrapping usa las dimensiones del backend, no las de la pantalla.
+pub fn write_fmt_on(backend: &mut impl VgaBackend, args: fmt::Arguments, color: u8, x: u8, y: u8) {
+    let dimensions = (backend.width(), backend.height());
+    let mut writer = FmtAt {
+        layout: TextLayout::new(x, y, x, dimensions),
+        sink: BackendSink { backend, color },
+    };
+    let _ = fmt::write(&mut writer, args);
+}
+
+/// Escribe texto con un `TextCursor` y retorna el cursor al final
+///
+/// A diferencia de `write_string_at`, el texto se escribe con
+/// `TextCursor::write_string`: los saltos de línea vuelven a la columna 0
+/// y al llegar a la última fila la pantalla se desplaza. El cursor
+/// retornado permite seguir escribiendo justo después del texto.
+///
+/// # Argumentos
+/// * `text` - El texto a escribir
+/// * `color` - Color del texto
+/// * `x` - Posición X inicial
+/// * `y` - Posición Y inicial
+pub fn write_string_at_cursor(text: &[u8], color: u8, x: u8, y: u8) -> TextCursor {
+    let mut cursor = TextCursor::new(x, y, color);
+    cursor.write_string(text);
+    cursor
+}
+
+/// Qué hace un `TextCursor` al pasar de la última fila
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Se queda en la última fila y sobrescribe su contenido
+    Clamp,
+    /// Desplaza la región de desplazamiento una línea hacia arriba
+    #[default]
+    Scroll,
+    /// Vuelve a la primera fila
+    Wrap,
+}
+
 /// Estructura para manejar un "cursor" de escritura
 ///
 /// Permite escribir texto secuencialmente sin tener que calcular
@@ -99,59 +960,128 @@ pub struct TextCursor {
     x: u8,
     y: u8,
     color: u8,
+    overflow: OverflowPolicy,
 }
 
 impl TextCursor {
     /// Crea un nuevo cursor en la posición especificada
-    pub fn new(x: u8, y: u8, color: u8) -> Self {
-        Self { x, y, color }
+    ///
+    /// Usa `OverflowPolicy::Scroll` al llegar a la última fila.
+    pub const fn new(x: u8, y: u8, color: u8) -> Self {
+        Self {
+            x,
+            y,
+            color,
+            overflow: OverflowPolicy::Scroll,
+        }
     }
 
     /// Escribe un carácter en la posición actual del cursor
+    ///
+    /// El byte se escribe tal cual, incluso si es un carácter de control,
+    /// para poder mostrar cualquier glifo de CP437.
     pub fn write_char(&mut self, character: u8) {
-        if self.is_valid_position() {
-            let syschar = structs::SysPrintableChar::new(character, self.color, self.x, self.y);
-            write_char_at(syschar);
-            self.advance();
-        }
+        self.write_char_on(&mut HardwareVga, character);
+    }
+
+    /// Igual que `write_char`, pero sobre cualquier backend
+    pub fn write_char_on(&mut self, backend: &mut impl VgaBackend, character: u8) {
+        let mut layout = self.layout_on(backend);
+        layout.place(character, &mut self.sink(backend));
+        self.sync(&layout);
     }
 
     /// Escribe una string en la posición actual del cursor
+    ///
+    /// Los saltos de línea, tabs y wrapping siguen las reglas de
+    /// `TextLayout`, con el margen en la columna 0.
     pub fn write_string(&mut self, text: &[u8]) {
+        self.write_string_on(&mut HardwareVga, text);
+    }
+
+    /// Igual que `write_string`, pero sobre cualquier backend
+    ///
+    /// El wrapping y la `OverflowPolicy` usan las dimensiones del backend.
+    pub fn write_string_on(&mut self, backend: &mut impl VgaBackend, text: &[u8]) {
+        let mut layout = self.layout_on(backend);
+        let mut sink = self.sink(backend);
         for &byte in text {
-            match byte {
-                b'\n' => self.new_line(),
-                b'\r' => self.carriage_return(),
-                _ => self.write_char(byte),
+            if !layout.feed(byte, true, &mut sink) {
+                break;
             }
         }
+        self.sync(&layout);
     }
 
-    /// Avanza el cursor una posición
-    fn advance(&mut self) {
-        self.x += 1;
-        if self.x >= VGA_WIDTH as u8 {
-            self.new_line();
+    /// Rellena con espacios hasta la próxima parada de tabulación
+    ///
+    /// Las paradas están cada `TAB_WIDTH` columnas contando desde la
+    /// columna 0 de la pantalla, no desde donde empezó el cursor. Si la
+    /// parada queda fuera del ancho, se rellena hasta el borde y el cursor
+    /// pasa a la siguiente línea con el próximo carácter.
+    pub fn tab(&mut self) {
+        self.tab_on(&mut HardwareVga);
+    }
+
+    /// Igual que `tab`, pero sobre cualquier backend
+    pub fn tab_on(&mut self, backend: &mut impl VgaBackend) {
+        self.write_string_on(backend, b"\t");
+    }
+
+    /// Última fila antes de aplicar la `OverflowPolicy`, exclusiva
+    ///
+    /// Con `Scroll` es el final de la región de desplazamiento, recortado
+    /// al alto del backend.
+    fn bottom_on(&self, backend: &impl VgaBackend) -> usize {
+        match self.overflow {
+            OverflowPolicy::Scroll => scroll_region().1.min(backend.height()),
+            OverflowPolicy::Clamp | OverflowPolicy::Wrap => backend.height(),
         }
     }
 
-    /// Mueve el cursor al inicio de la siguiente línea
-    fn new_line(&mut self) {
-        self.x = 0;
-        self.y += 1;
-        if self.y >= VGA_HEIGHT as u8 {
-            self.y = VGA_HEIGHT as u8 - 1; // Mantener en la última línea
+    /// Distribución que continúa desde la posición del cursor
+    fn layout_on(&self, backend: &impl VgaBackend) -> TextLayout {
+        TextLayout::new(
+            self.x,
+            self.y,
+            0,
+            (backend.width(), self.bottom_on(backend)),
+        )
+    }
+
+    /// Destino de la distribución con el color y la política del cursor
+    fn sink<'a, B: VgaBackend>(&self, backend: &'a mut B) -> CursorSink<'a, B> {
+        CursorSink {
+            bottom: self.bottom_on(backend),
+            backend,
+            color: self.color,
+            overflow: self.overflow,
         }
     }
 
-    /// Mueve el cursor al inicio de la línea actual
-    fn carriage_return(&mut self) {
-        self.x = 0;
+    /// Copia la posición final de una distribución al cursor
+    fn sync(&mut self, layout: &TextLayout) {
+        (self.x, self.y) = layout.position();
     }
 
-    /// Verifica si el cursor está en una posición válida
-    fn is_valid_position(&self) -> bool {
-        (self.x as usize) < VGA_WIDTH && (self.y as usize) < VGA_HEIGHT
+    /// Borra desde el cursor hasta el final de la fila actual
+    ///
+    /// Escribe espacios con el color del cursor; la posición no cambia.
+    pub fn clear_to_eol(&self) {
+        let w = (width() as u8).saturating_sub(self.x);
+        clear_region(Rect::new(self.x, self.y, w, 1), self.color);
+    }
+
+    /// Borra desde el cursor hasta la esquina inferior derecha
+    ///
+    /// Limpia el resto de la fila actual y todas las filas siguientes con
+    /// el color del cursor; la posición no cambia.
+    pub fn clear_to_eos(&self) {
+        self.clear_to_eol();
+
+        let below = self.y.saturating_add(1);
+        let h = (height() as u8).saturating_sub(below);
+        clear_region(Rect::new(0, below, width() as u8, h), self.color);
     }
 
     /// Obtiene la posición actual del cursor
@@ -159,8 +1089,98 @@ impl TextCursor {
         (self.x, self.y)
     }
 
+    /// Mueve el cursor a una posición específica
+    pub fn set_position(&mut self, x: u8, y: u8) {
+        self.x = x;
+        self.y = y;
+    }
+
     /// Cambia el color del cursor
     pub fn set_color(&mut self, color: u8) {
         self.color = color;
     }
+
+    /// Cambia qué hace el cursor al pasar de la última fila
+    pub fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.overflow = policy;
+    }
+}
+
+/// Destino de `TextLayout` que escribe lo que distribuye un `TextCursor`
+struct CursorSink<'a, B: VgaBackend> {
+    backend: &'a mut B,
+    color: u8,
+    overflow: OverflowPolicy,
+    /// Fila donde se aplica la política (ver `TextCursor::bottom_on`)
+    bottom: usize,
+}
+
+impl<B: VgaBackend> LayoutSink for CursorSink<'_, B> {
+    fn put(&mut self, character: u8, x: u8, y: u8) -> bool {
+        self.backend
+            .put_char(structs::SysPrintableChar::new(character, self.color, x, y));
+        true
+    }
+
+    fn overflow(&mut self) -> Option<u8> {
+        let last = self.bottom.saturating_sub(1) as u8;
+        match self.overflow {
+            OverflowPolicy::Clamp => Some(last), // Mantener en la última línea
+            OverflowPolicy::Scroll => {
+                scroll_up_on(self.backend, 1, self.color);
+                Some(last)
+            }
+            OverflowPolicy::Wrap => Some(0),
+        }
+    }
+}
+
+impl fmt::Write for TextCursor {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_string(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Writer global usado por `print!` y `println!`
+///
+/// # Contrato con interrupciones
+/// El writer está protegido por un spinlock. Para que una rutina de
+/// interrupción (ISR) nunca encuentre el lock tomado por el código que
+/// interrumpió, todo acceso debe hacerse con las interrupciones
+/// deshabilitadas, como hace `_print`. Una ISR que escriba en pantalla
+/// debe usar `print!` y no mantener el lock más allá de la escritura.
+/// El panic handler libera el lock a la fuerza antes de escribir.
+pub static WRITER: SpinLock<TextCursor> = SpinLock::new(TextCursor::new(0, 0, LIGHT_GRAY));
+
+/// Implementación de `print!`: escribe en el writer global
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments) {
+    use core::fmt::Write;
+
+    without_interrupts(|| {
+        cursor::while_writing(|| {
+            let _ = WRITER.lock().write_fmt(args);
+        });
+        scrollback::record_fmt(args);
+    });
+}
+
+/// Imprime texto formateado en el writer global
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::io::stdout::_print(format_args!($($arg)*))
+    };
+}
+
+/// Imprime texto formateado en el writer global seguido de un salto de línea
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::print!("{}\n", format_args!($($arg)*))
+    };
 }
@@ -0,0 +1,64 @@
+//! Patrón de prueba de la pantalla
+//!
+//! Sirve para verificar en hardware o emuladores nuevos que el buffer de
+//! texto, las dimensiones detectadas y la paleta son las esperadas.
+
+use crate::io::stdout::colors::{WHITE, make_color};
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{
+    clear_screen, default_color, fill_horizontal, height, width, write_char_at,
+};
+
+/// Columnas reservadas al inicio de cada fila para el número de fila y
+/// el índice del fondo
+const LABEL_WIDTH: u8 = 3;
+
+/// Dígito hexadecimal de un índice de color (0-15)
+const fn hex_digit(value: u8) -> u8 {
+    match value {
+        0..=9 => b'0' + value,
+        _ => b'A' + value - 10,
+    }
+}
+
+/// Llena la pantalla con un patrón de prueba de colores
+///
+/// La primera fila marca las columnas con el último dígito de su número.
+/// Cada fila siguiente muestra los 16 colores de frente como bloques con
+/// el índice del color (en hexadecimal) sobre un fondo que cambia de fila
+/// en fila; al inicio de la fila van su número y el índice del fondo. Un
+/// dígito que no coincide con su color delata un problema de paleta, y
+/// filas o columnas corridas, un problema de dimensiones. Los números de
+/// fila y columna usan el color por defecto.
+///
+/// Con el parpadeo habilitado, los fondos 8-15 parpadean en lugar de
+/// mostrarse brillantes.
+pub fn draw_test_pattern() {
+    clear_screen(default_color());
+
+    let columns = width() as u8;
+    for x in 0..columns {
+        write_char_at(SysPrintableChar::new_default(b'0' + x % 10, x, 0));
+    }
+
+    let block_width = (columns.saturating_sub(LABEL_WIDTH) / 16).max(1);
+    for y in 1..height() as u8 {
+        let background = (y - 1) % 16;
+
+        write_char_at(SysPrintableChar::new_default(b'0' + y / 10 % 10, 0, y));
+        write_char_at(SysPrintableChar::new_default(b'0' + y % 10, 1, y));
+        write_char_at(SysPrintableChar::new_fg_bg(
+            hex_digit(background),
+            WHITE,
+            background,
+            2,
+            y,
+        ));
+
+        for foreground in 0..16 {
+            let x = LABEL_WIDTH + foreground * block_width;
+            let color = make_color(foreground, background);
+            fill_horizontal(hex_digit(foreground), color, x, y, block_width);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Prompt con cuenta regresiva
+//!
+//! El clásico "Press any key to enter setup... 5" del arranque: un texto
+//! seguido de un número que baja cada segundo hasta que se presiona una
+//! tecla o llega a cero.
+
+use crate::io::stdin::keyboard;
+use crate::io::stdout::{WRITER, default_color, write_string_at_cursor};
+use crate::sync::without_interrupts;
+use crate::timer::{self, TICKS_PER_SECOND};
+use core::fmt::Write;
+use heapless::String;
+
+/// Muestra `text` con una cuenta regresiva y espera una tecla
+///
+/// El texto se escribe en la posición del writer global y el número
+/// justo después, actualizándose en el lugar una vez por segundo. La
+/// tecla presionada se consume. Al terminar, el writer queda al inicio de
+/// la línea siguiente.
+///
+/// # Argumentos
+/// * `text` - Texto que precede al número
+/// * `seconds` - Segundos de espera; con 0 retorna `false` sin esperar
+///
+/// # Retorna
+/// `true` si se presionó una tecla antes de que la cuenta llegue a cero
+pub fn countdown_prompt(text: &[u8], seconds: u8) -> bool {
+    let (x, y) = without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.write_string(text);
+        writer.position()
+    });
+
+    let start = timer::ticks();
+    let mut shown = None;
+    let mut end = (x, y);
+
+    let pressed = loop {
+        if keyboard::poll_keyboard_event().is_some() {
+            break true;
+        }
+
+        let elapsed = (timer::ticks() - start) / TICKS_PER_SECOND;
+        let remaining = (seconds as u64).saturating_sub(elapsed);
+        if remaining == 0 {
+            break false;
+        }
+
+        if shown != Some(remaining) {
+            let mut digits: String<20> = String::new();
+            let _ = write!(digits, "{}", remaining);
+            let cursor = write_string_at_cursor(digits.as_bytes(), default_color(), x, y);
+            cursor.clear_to_eol();
+            end = cursor.position();
+            shown = Some(remaining);
+        }
+        core::hint::spin_loop();
+    };
+
+    without_interrupts(|| {
+        let mut writer = WRITER.lock();
+        writer.set_position(end.0, end.1);
+        writer.write_string(b"\n");
+    });
+    pressed
+}
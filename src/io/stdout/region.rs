@@ -0,0 +1,95 @@
+//! Writer limitado a una región rectangular de la pantalla
+//!
+//! A diferencia del writer global, no desplaza la pantalla: el texto que
+//! no entra en la región se descarta. Es útil para paneles fijos (HUD,
+//! barras de estado) dibujados sobre un área principal que sí se desplaza.
+
+use crate::io::stdout::cp437;
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::write_char_at;
+use core::fmt;
+
+/// Writer que recorta el texto a una región
+///
+/// `\n` pasa a la siguiente línea de la región y `\r` vuelve al inicio de
+/// la línea actual. El texto que pasa del borde derecho se descarta hasta
+/// el próximo salto de línea, y todo lo que queda debajo de la última
+/// línea se descarta.
+pub struct RegionWriter {
+    x: u8,
+    y: u8,
+    w: u8,
+    h: u8,
+    color: u8,
+    /// Posición dentro de la región (columna, fila)
+    cursor: (u8, u8),
+}
+
+impl RegionWriter {
+    /// Crea un writer para la región indicada
+    ///
+    /// # Argumentos
+    /// * `x`, `y` - Esquina superior izquierda de la región
+    /// * `w`, `h` - Ancho y alto de la región
+    /// * `color` - Color del texto
+    pub const fn new(x: u8, y: u8, w: u8, h: u8, color: u8) -> Self {
+        Self {
+            x,
+            y,
+            w,
+            h,
+            color,
+            cursor: (0, 0),
+        }
+    }
+
+    /// Llena la región con espacios y vuelve el cursor al inicio
+    pub fn clear(&mut self) {
+        for row in 0..self.h {
+            for col in 0..self.w {
+                write_char_at(SysPrintableChar::new(
+                    b' ',
+                    self.color,
+                    self.x.saturating_add(col),
+                    self.y.saturating_add(row),
+                ));
+            }
+        }
+        self.cursor = (0, 0);
+    }
+
+    /// Cambia el color del texto
+    pub fn set_color(&mut self, color: u8) {
+        self.color = color;
+    }
+
+    /// Escribe un byte CP437 en la posición actual
+    pub fn write_byte(&mut self, byte: u8) {
+        let (col, row) = self.cursor;
+
+        match byte {
+            b'\n' => self.cursor = (0, row.saturating_add(1)),
+            b'\r' => self.cursor = (0, row),
+            _ => {
+                if col < self.w && row < self.h {
+                    write_char_at(SysPrintableChar::new(
+                        byte,
+                        self.color,
+                        self.x.saturating_add(col),
+                        self.y.saturating_add(row),
+                    ));
+                }
+                self.cursor = (col.saturating_add(1), row);
+            }
+        }
+    }
+}
+
+impl fmt::Write for RegionWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for character in s.chars() {
+            self.write_byte(cp437::from_char_lossy(character));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+//! Back buffer de pantalla
+//!
+//! Permite componer un cuadro completo en memoria y copiarlo al buffer VGA
+//! de una sola vez con `present`, evitando el parpadeo de redibujar celda
+//! por celda directamente en pantalla.
+//!
+//! El buffer recuerda qué rango de celdas se modificó desde el último
+//! `present` y una copia de lo que ya se mostró; solo se escriben las
+//! celdas del rango que difieren de lo mostrado, así que una edición de un
+//! carácter cuesta una escritura en lugar de una pantalla completa, aunque
+//! el cuadro se redibuje entero.
+
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{VGA_MAX_CELLS, flush, height, make_cell, vga_base, width};
+
+/// Copia en memoria del contenido de la pantalla
+pub struct Screen {
+    cells: [u16; VGA_MAX_CELLS],
+    /// Contenido copiado a la pantalla en el último `present`
+    shown: [u16; VGA_MAX_CELLS],
+    /// Rango de celdas modificadas desde el último `present` (inicio
+    /// inclusivo, fin exclusivo)
+    dirty: Option<(usize, usize)>,
+    /// Si es verdadero, `shown` no refleja la pantalla y el próximo
+    /// `present` escribe todo el rango sin comparar
+    stale: bool,
+}
+
+impl Screen {
+    /// Crea un back buffer vacío (espacios en negro)
+    ///
+    /// Todo el buffer empieza marcado como modificado, para que el primer
+    /// `present` lo copie completo.
+    pub const fn new() -> Self {
+        Self {
+            cells: [make_cell(b' ', 0); VGA_MAX_CELLS],
+            shown: [0; VGA_MAX_CELLS],
+            dirty: Some((0, VGA_MAX_CELLS)),
+            stale: true,
+        }
+    }
+
+    /// Cambia una celda, marcándola como modificada si su valor cambió
+    fn set(&mut self, index: usize, cell: u16) {
+        let Some(current) = self.cells.get_mut(index) else {
+            return;
+        };
+        if *current == cell {
+            return;
+        }
+
+        *current = cell;
+        self.dirty = match self.dirty {
+            Some((start, end)) => Some((start.min(index), end.max(index + 1))),
+            None => Some((index, index + 1)),
+        };
+    }
+
+    /// Marca todo el buffer como modificado
+    ///
+    /// Necesario si la pantalla se escribió por otro camino (por ejemplo,
+    /// después de `restore_screen`) y el próximo `present` debe copiar
+    /// todo de nuevo.
+    pub fn mark_all_dirty(&mut self) {
+        self.dirty = Some((0, VGA_MAX_CELLS));
+        self.stale = true;
+    }
+
+    /// Llena todo el buffer con espacios del color indicado
+    pub fn clear(&mut self, color: u8) {
+        let blank = make_cell(b' ', color);
+        for index in 0..width() * height() {
+            self.set(index, blank);
+        }
+    }
+
+    /// Escribe un carácter en el buffer
+    ///
+    /// Los caracteres fuera de la pantalla se ignoran silenciosamente.
+    pub fn write_char(&mut self, syschar: SysPrintableChar) {
+        if !syschar.is_valid() {
+            return;
+        }
+
+        let index = syschar.y as usize * width() + syschar.x as usize;
+        self.set(index, make_cell(syschar.character, syschar.color));
+    }
+
+    /// Escribe un texto en el buffer con las mismas reglas que `new_string`
+    pub fn write_string(&mut self, text: &[u8], color: u8, x: u8, y: u8) {
+        for syschar in SysPrintableChar::new_string(text, color, x, y) {
+            self.write_char(syschar);
+        }
+    }
+
+    /// Copia a la pantalla las celdas que cambiaron desde el último
+    /// `present`
+    ///
+    /// # Retorna
+    /// La cantidad de celdas escritas en el buffer VGA
+    pub fn present(&mut self) -> usize {
+        let Some((start, end)) = self.dirty.take() else {
+            return 0;
+        };
+
+        let visible = (width() * height()).min(VGA_MAX_CELLS);
+        let vga = vga_base() as *mut u16;
+        let mut written = 0;
+
+        for index in start..end.min(visible) {
+            let cell = self.cells[index];
+            if !self.stale && self.shown[index] == cell {
+                continue;
+            }
+
+            unsafe {
+                core::ptr::write_volatile(vga.add(index), cell);
+            }
+            self.shown[index] = cell;
+            written += 1;
+        }
+        self.stale = false;
+        flush();
+
+        written
+    }
+}
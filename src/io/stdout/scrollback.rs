@@ -0,0 +1,116 @@
+//! Historial de la consola
+//!
+//! Guarda como líneas lógicas (sin el wrapping de la pantalla) el texto
+//! escrito en la consola con `print!`, el registro directo de `log` y las
+//! líneas del shell. Es la fuente de la que `reflow` vuelve a dibujar la
+//! pantalla cuando cambian sus dimensiones. Los colores no se guardan.
+
+use crate::io::stdout::{
+    WRITER, clear_screen, default_color, reset_scroll_region, set_cursor_position, set_dimensions,
+};
+use crate::sync::{SpinLock, without_interrupts};
+use core::fmt;
+use heapless::{Deque, Vec};
+
+/// Cantidad de líneas que guarda el historial (se descartan las más viejas)
+pub const SCROLLBACK_LINES: usize = 64;
+
+/// Largo máximo de una línea lógica; lo que sobra se descarta
+pub const SCROLLBACK_LINE_LENGTH: usize = 160;
+
+type Line = Vec<u8, SCROLLBACK_LINE_LENGTH>;
+
+/// Líneas guardadas; la última es la línea en curso, todavía sin `\n`
+static SCROLLBACK: SpinLock<Deque<Line, SCROLLBACK_LINES>> = SpinLock::new(Deque::new());
+
+/// Agrega texto al historial
+///
+/// Cada `\n` termina la línea en curso y empieza una nueva.
+pub fn record(text: &[u8]) {
+    let mut lines = SCROLLBACK.lock();
+
+    for &byte in text {
+        if lines.is_empty() {
+            let _ = lines.push_back(Line::new());
+        }
+
+        if byte == b'\n' {
+            if lines.is_full() {
+                lines.pop_front();
+            }
+            let _ = lines.push_back(Line::new());
+        } else if let Some(line) = lines.back_mut() {
+            let _ = line.push(byte);
+        }
+    }
+}
+
+/// Agrega texto formateado al historial
+pub fn record_fmt(args: fmt::Arguments) {
+    struct Recorder;
+
+    impl fmt::Write for Recorder {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            record(s.as_bytes());
+            Ok(())
+        }
+    }
+
+    let _ = fmt::Write::write_fmt(&mut Recorder, args);
+}
+
+/// Vacía el historial, por ejemplo al limpiar la pantalla
+pub fn clear() {
+    SCROLLBACK.lock().clear();
+}
+
+/// Cambia las dimensiones de la pantalla y la vuelve a dibujar desde el
+/// historial
+///
+/// Pensada para después de cambiar de modo de texto (por ejemplo de 80x25
+/// a 80x50), cuando el contenido del buffer ya no corresponde a la nueva
+/// geometría. Solo cambia la geometría lógica (`set_dimensions`): quien
+/// llama debe haber programado antes el modo de video, ya que el kernel
+/// todavía no tiene una rutina para cambiarlo. La pantalla se limpia y
+/// las líneas del historial se escriben de nuevo con el wrapping del
+/// nuevo ancho; si no entran, quedan visibles las últimas. La región de
+/// desplazamiento vuelve a la pantalla completa.
+///
+/// El cursor no conserva su posición anterior: queda al final de la línea
+/// en curso (la última del historial), que es donde continuaría la salida,
+/// y el cursor de hardware se mueve ahí.
+///
+/// # Retorna
+/// `false` si las dimensiones no son válidas; en ese caso la pantalla no
+/// se modifica
+pub fn reflow(new_width: usize, new_height: usize) -> bool {
+    if !set_dimensions(new_width, new_height) {
+        return false;
+    }
+
+    reset_scroll_region();
+    clear_screen(default_color());
+
+    without_interrupts(|| {
+        WRITER.lock().set_position(0, 0);
+
+        // `log` registra en el historial con el writer tomado, así que acá
+        // nunca se toman los dos locks juntos: cada línea se copia antes
+        // de escribirla
+        for index in 0.. {
+            let Some(line) = SCROLLBACK.lock().iter().nth(index).cloned() else {
+                break;
+            };
+
+            let mut writer = WRITER.lock();
+            if index > 0 {
+                writer.write_string(b"\n");
+            }
+            writer.write_string(&line);
+        }
+
+        let (x, y) = WRITER.lock().position();
+        set_cursor_position(x, y);
+    });
+    true
+}
@@ -0,0 +1,53 @@
+//! Línea de estado fija en la primera o la última fila
+//!
+//! La fila reservada queda fuera de la región de desplazamiento, así que
+//! el texto se mantiene mientras el resto de la pantalla se desplaza.
+
+use crate::io::stdout::structs::SysPrintableChar;
+use crate::io::stdout::{clear_line, height, set_scroll_region, theme, try_write_char_at, width};
+
+/// Fila donde se muestra la línea de estado
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusPosition {
+    Top,
+    Bottom,
+}
+
+impl StatusPosition {
+    /// Fila reservada para la línea de estado
+    pub fn row(self) -> u8 {
+        match self {
+            StatusPosition::Top => 0,
+            StatusPosition::Bottom => (height() - 1) as u8,
+        }
+    }
+}
+
+/// Escribe la línea de estado y la excluye de la región de desplazamiento
+///
+/// La fila se limpia antes de escribir, así que un texto más corto que el
+/// anterior no deja restos; el texto que no entra en el ancho de la
+/// pantalla se recorta.
+///
+/// # Argumentos
+/// * `text` - Texto de la línea de estado
+/// * `color` - Color del texto y del relleno
+/// * `position` - Fila reservada (primera o última)
+pub fn set_status_line(text: &[u8], color: u8, position: StatusPosition) {
+    let row = position.row();
+
+    clear_line(row, color);
+    for (x, &character) in text.iter().take(width()).enumerate() {
+        try_write_char_at(SysPrintableChar::new(character, color, x as u8, row));
+    }
+
+    match position {
+        StatusPosition::Top => set_scroll_region(1, height()),
+        StatusPosition::Bottom => set_scroll_region(0, height() - 1),
+    };
+}
+
+/// Igual que `set_status_line`, con el color de estado del tema activo
+pub fn set_status_line_themed(text: &[u8], position: StatusPosition) {
+    set_status_line(text, theme::theme().status_color(), position);
+}
@@ -1,4 +1,9 @@
-use crate::io::stdout::{VGA_HEIGHT, VGA_WIDTH};
+use crate::io::stdout::backend::{HardwareVga, SizedBackend, VgaBackend};
+use crate::io::stdout::colors::make_color_safe;
+use crate::io::stdout::layout::{LayoutSink, TextLayout};
+use crate::io::stdout::{
+    TAB_WIDTH, TextCursor, cp437, default_color, height, make_cell, width, write_fmt_on,
+};
 
 /// Representa un carácter imprimible en el sistema VGA
 ///
@@ -32,6 +37,31 @@ impl SysPrintableChar {
         }
     }
 
+    /// Crea un carácter imprimible a partir de colores separados
+    ///
+    /// Empaqueta `fg` y `bg` con `make_color_safe`, así que un índice
+    /// fuera de 0x0-0xF pasa a `LIGHT_GRAY` (frente) o `BLACK` (fondo) en
+    /// lugar de mezclarse con la otra mitad del byte.
+    ///
+    /// # Argumentos
+    /// * `character` - El carácter ASCII a mostrar
+    /// * `fg` - Color del carácter (0x0-0xF)
+    /// * `bg` - Color del fondo (0x0-0xF)
+    /// * `x` - Posición horizontal (0-79)
+    /// * `y` - Posición vertical (0-24)
+    #[inline(always)]
+    pub const fn new_fg_bg(character: u8, fg: u8, bg: u8, x: u8, y: u8) -> Self {
+        Self::new(character, make_color_safe(fg, bg), x, y)
+    }
+
+    /// Crea un nuevo carácter imprimible con el color por defecto
+    ///
+    /// El color se toma de `stdout::default_color()` en el momento de la
+    /// llamada.
+    pub fn new_default(character: u8, x: u8, y: u8) -> Self {
+        Self::new(character, default_color(), x, y)
+    }
+
     /// Crea una serie de caracteres desde un string
     ///
     /// Esta función toma un buffer de bytes y lo convierte en una serie
@@ -52,51 +82,105 @@ impl SysPrintableChar {
         start_x: u8,
         start_y: u8,
     ) -> heapless::Vec<Self, 2000> {
-        let mut chars = heapless::Vec::new();
-        let mut position = TextPosition::new(start_x, start_y);
+        HardwareVga.new_string(buffer, color, start_x, start_y)
+    }
 
-        for &byte in buffer {
-            // Verificar límites antes de procesar
-            if position.is_out_of_bounds() || chars.is_full() {
-                break;
-            }
+    /// Igual que `new_string`, pero informa si el texto no entró completo
+    ///
+    /// # Retorna
+    /// Los caracteres distribuidos y `true` si se descartó parte del texto,
+    /// ya sea por salirse de la pantalla o por llenarse el vector. Un salto
+    /// de línea final que cae debajo de la última fila también cuenta como
+    /// truncado.
+    pub fn try_new_string(
+        buffer: &[u8],
+        color: u8,
+        start_x: u8,
+        start_y: u8,
+    ) -> (heapless::Vec<Self, 2000>, bool) {
+        let size = (width(), height());
+        Self::layout(buffer.iter().copied(), color, start_x, start_y, size, false)
+    }
 
-            match byte {
-                b'\n' => {
-                    position.new_line(start_x);
-                }
-                b'\r' => {
-                    position.carriage_return(start_x);
-                }
-                b'\t' => {
-                    // Tab = 4 espacios
-                    for _ in 0..4 {
-                        if position.is_out_of_bounds() || chars.is_full() {
-                            break;
-                        }
-                        let _ = chars.push(Self::new(b' ', color, position.x, position.y));
-                        position.advance();
-                    }
-                }
-                printable_char if printable_char.is_ascii_graphic() || printable_char == b' ' => {
-                    // Manejar wrap automático
-                    if position.x >= VGA_WIDTH as u8 {
-                        position.new_line(start_x);
-                        if position.is_out_of_bounds() {
-                            break;
-                        }
-                    }
-
-                    let _ = chars.push(Self::new(printable_char, color, position.x, position.y));
-                    position.advance();
-                }
-                _ => {
-                    // Ignorar caracteres no imprimibles
-                }
+    /// Crea una serie de caracteres para una pantalla de tamaño fijo
+    ///
+    /// Igual que `new_string`, pero el wrapping y los límites usan las
+    /// dimensiones `W` x `H` indicadas en tiempo de compilación en lugar
+    /// de las del modo de video actual. Permite distribuir texto para otros
+    /// modos o para una pantalla virtual pequeña (por ejemplo 10x4).
+    ///
+    /// # Tipos genéricos
+    /// * `W` - Ancho de la pantalla en columnas
+    /// * `H` - Alto de la pantalla en filas
+    pub fn new_string_sized<const W: usize, const H: usize>(
+        buffer: &[u8],
+        color: u8,
+        start_x: u8,
+        start_y: u8,
+    ) -> heapless::Vec<Self, 2000> {
+        Self::layout(
+            buffer.iter().copied(),
+            color,
+            start_x,
+            start_y,
+            (W, H),
+            false,
+        )
+        .0
+    }
+
+    /// Crea una serie de caracteres desde un string UTF-8
+    ///
+    /// Cada carácter se convierte a su equivalente en CP437 (letras
+    /// acentuadas, dibujo de cajas, símbolos); los que no existen en CP437
+    /// se muestran como '?'. El wrapping es idéntico al de `new_string`.
+    ///
+    /// # Argumentos
+    /// * `text` - El texto a convertir
+    /// * `color` - Color a aplicar a todos los caracteres
+    /// * `start_x` - Posición X inicial
+    /// * `start_y` - Posición Y inicial
+    pub fn new_utf8_string(
+        text: &str,
+        color: u8,
+        start_x: u8,
+        start_y: u8,
+    ) -> heapless::Vec<Self, 2000> {
+        let bytes = text.chars().map(cp437::from_char_lossy);
+        let size = (width(), height());
+        Self::layout(bytes, color, start_x, start_y, size, true).0
+    }
+
+    /// Distribuye bytes en la pantalla aplicando saltos de línea y wrapping
+    ///
+    /// `size` son las dimensiones (ancho, alto) de la pantalla destino.
+    /// Si `extended` es verdadero, los bytes 0x80-0xFF se tratan como
+    /// caracteres CP437 imprimibles en lugar de ignorarse. El `bool` del
+    /// resultado indica si la distribución terminó antes de consumir todo
+    /// el texto.
+    pub(super) fn layout(
+        bytes: impl Iterator<Item = u8>,
+        color: u8,
+        start_x: u8,
+        start_y: u8,
+        size: (usize, usize),
+        extended: bool,
+    ) -> (heapless::Vec<Self, 2000>, bool) {
+        let mut sink = CharSink {
+            chars: heapless::Vec::new(),
+            color,
+        };
+        let mut layout = TextLayout::new(start_x, start_y, start_x, size);
+
+        let mut truncated = false;
+        for byte in bytes {
+            if !layout.feed(byte, extended, &mut sink) {
+                truncated = true;
+                break;
             }
         }
 
-        chars
+        (sink.chars, truncated)
     }
 
     /// Crea un iterador de caracteres desde un string (sin allocación)
@@ -109,73 +193,268 @@ impl SysPrintableChar {
         start_x: u8,
         start_y: u8,
     ) -> impl Iterator<Item = SysPrintableChar> + '_ {
-        let mut position = TextPosition::new(start_x, start_y);
-
-        buffer.iter().filter_map(move |&byte| {
-            if position.is_out_of_bounds() {
-                return None;
-            }
+        let mut layout = TextLayout::new(start_x, start_y, start_x, (width(), height()));
 
-            match byte {
-                b'\n' => {
-                    position.new_line(start_x);
-                    None
-                }
-                b'\r' => {
-                    position.carriage_return(start_x);
-                    None
-                }
-                printable_char if printable_char.is_ascii_graphic() || printable_char == b' ' => {
-                    // Wrap automático
-                    if position.x >= VGA_WIDTH as u8 {
-                        position.new_line(start_x);
-                        if position.is_out_of_bounds() {
-                            return None;
-                        }
-                    }
-
-                    let result =
-                        SysPrintableChar::new(printable_char, color, position.x, position.y);
-                    position.advance();
-                    Some(result)
-                }
-                _ => None, // Ignorar caracteres no imprimibles
-            }
+        // Un byte produce como mucho `TAB_WIDTH` caracteres (un tab)
+        buffer.iter().flat_map(move |&byte| {
+            let mut sink = CharSink::<{ TAB_WIDTH as usize }> {
+                chars: heapless::Vec::new(),
+                color,
+            };
+            layout.feed(byte, false, &mut sink);
+            sink.chars
         })
     }
 
+    /// Verifica si el carácter es ASCII imprimible (incluido el espacio)
+    pub fn is_printable(&self) -> bool {
+        self.character.is_ascii_graphic() || self.character == b' '
+    }
+
+    /// Carácter lógico que muestra la celda
+    ///
+    /// Los bytes que no son ASCII imprimible (control, CP437 extendido) se
+    /// devuelven como '.', para que los volcados de pantalla sean legibles.
+    pub fn to_char(self) -> char {
+        if self.is_printable() {
+            self.character as char
+        } else {
+            '.'
+        }
+    }
+
     /// Verifica si el carácter está dentro de los límites de la pantalla
     pub fn is_valid(&self) -> bool {
-        (self.x as usize) < VGA_WIDTH && (self.y as usize) < VGA_HEIGHT
+        self.is_valid_in(width(), height())
+    }
+
+    /// Verifica si el carácter está dentro de una pantalla de tamaño dado
+    pub fn is_valid_in(&self, screen_width: usize, screen_height: usize) -> bool {
+        (self.x as usize) < screen_width && (self.y as usize) < screen_height
     }
-}
 
-/// Estructura auxiliar para manejar posiciones de texto
-#[derive(Debug, Clone, Copy)]
-struct TextPosition {
-    x: u8,
-    y: u8,
+    /// Verifica si el carácter está dentro de una pantalla de `W` x `H`
+    ///
+    /// Igual que `is_valid_in`, con las dimensiones fijadas en tiempo de
+    /// compilación como en `new_string_sized`.
+    pub const fn is_valid_sized<const W: usize, const H: usize>(&self) -> bool {
+        (self.x as usize) < W && (self.y as usize) < H
+    }
 }
 
-impl TextPosition {
-    const fn new(x: u8, y: u8) -> Self {
-        Self { x, y }
+/// Calcula cuánto ocupa un texto sin dibujarlo
+///
+/// Aplica los mismos saltos de línea, tabs y wrapping que `new_string`
+/// con el texto empezando en la fila 0. Las filas que no entran en la
+/// pantalla no se cuentan.
+///
+/// # Argumentos
+/// * `text` - El texto a medir
+/// * `start_x` - Columna donde empezaría el texto
+///
+/// # Retorna
+/// `(ancho, alto)` del rectángulo que encierra los caracteres visibles,
+/// medido desde `start_x`; `(0, 0)` si el texto no tiene caracteres
+/// visibles
+pub fn measure_string(text: &[u8], start_x: u8) -> (u8, u8) {
+    /// Destino que solo registra hasta dónde llegan los caracteres
+    struct Extent {
+        right: Option<u8>,
+        bottom: u8,
+    }
+
+    impl LayoutSink for Extent {
+        fn put(&mut self, _character: u8, x: u8, y: u8) -> bool {
+            self.right = self.right.max(Some(x));
+            self.bottom = self.bottom.max(y);
+            true
+        }
     }
 
-    fn advance(&mut self) {
-        self.x += 1;
+    let mut extent = Extent {
+        right: None,
+        bottom: 0,
+    };
+    let mut layout = TextLayout::new(start_x, 0, start_x, (width(), height()));
+
+    for &byte in text {
+        if !layout.feed(byte, false, &mut extent) {
+            break;
+        }
     }
 
-    fn new_line(&mut self, start_x: u8) {
-        self.y += 1;
-        self.x = start_x;
+    match extent.right {
+        Some(right) => (right + 1 - start_x, extent.bottom + 1),
+        None => (0, 0),
     }
+}
+
+/// Desplaza una secuencia de caracteres a otra posición de la pantalla
+///
+/// Permite generar texto en el origen (0, 0) y ubicarlo después donde se
+/// necesite. Los caracteres cuya nueva posición queda fuera de la pantalla
+/// (incluidas las coordenadas negativas) se descartan.
+///
+/// # Argumentos
+/// * `iter` - Los caracteres a desplazar
+/// * `dx` - Desplazamiento horizontal
+/// * `dy` - Desplazamiento vertical
+pub fn translate(
+    iter: impl Iterator<Item = SysPrintableChar>,
+    dx: i8,
+    dy: i8,
+) -> impl Iterator<Item = SysPrintableChar> {
+    iter.filter_map(move |syschar| {
+        let x = syschar.x.checked_add_signed(dx)?;
+        let y = syschar.y.checked_add_signed(dy)?;
+
+        let moved = SysPrintableChar { x, y, ..syschar };
+        moved.is_valid().then_some(moved)
+    })
+}
 
-    fn carriage_return(&mut self, start_x: u8) {
-        self.x = start_x;
+/// Destino de `TextLayout` que junta los caracteres en un vector
+struct CharSink<const N: usize> {
+    chars: heapless::Vec<SysPrintableChar, N>,
+    color: u8,
+}
+
+impl<const N: usize> LayoutSink for CharSink<N> {
+    fn put(&mut self, character: u8, x: u8, y: u8) -> bool {
+        self.chars
+            .push(SysPrintableChar::new(character, self.color, x, y))
+            .is_ok()
     }
+}
+
+/// Verifica la distribución de texto en una pantalla virtual de 10x4
+///
+/// Distribuye con `new_string_sized` un texto que hace wrapping y tiene
+/// un salto de línea, y otro con más líneas de las que entran, y compara
+/// la posición exacta de cada carácter. Además verifica que todos los
+/// puntos de entrada de `TextLayout` coincidan (ver `check_entry_points`)
+/// y que ninguno dé la vuelta a una coordenada `u8`
+/// (ver `check_coordinate_overflow`).
+/// Se ejecuta al arrancar porque el kernel no tiene tests de host.
+///
+/// # Retorna
+/// `true` si todos los caracteres quedaron donde se esperaba
+pub fn layout_self_check() -> bool {
+    const COLOR: u8 = 0x07;
+
+    let matches = |chars: &[SysPrintableChar], expected: &[(u8, u8, u8)]| {
+        chars.len() == expected.len()
+            && chars
+                .iter()
+                .zip(expected)
+                .all(|(c, &(character, x, y))| c.character == character && c.x == x && c.y == y)
+    };
 
-    fn is_out_of_bounds(&self) -> bool {
-        self.y >= VGA_HEIGHT as u8
+    // "hello worl" llena la fila 0, la 'd' pasa a la fila 1 y "ok" va
+    // después del salto de línea
+    let wrapped = SysPrintableChar::new_string_sized::<10, 4>(b"hello world\nok", COLOR, 0, 0);
+    let mut expected: heapless::Vec<(u8, u8, u8), 13> = heapless::Vec::new();
+    for (col, &character) in b"hello worl".iter().enumerate() {
+        let _ = expected.push((character, col as u8, 0));
     }
+    let _ = expected.extend_from_slice(&[(b'd', 0, 1), (b'o', 0, 2), (b'k', 1, 2)]);
+
+    // La quinta línea no entra en las 4 filas y se descarta
+    let clipped = SysPrintableChar::new_string_sized::<10, 4>(b"a\nb\nc\nd\ne", COLOR, 0, 0);
+    let clipped_expected = [(b'a', 0, 0), (b'b', 0, 1), (b'c', 0, 2), (b'd', 0, 3)];
+
+    // El mismo texto escrito con `write_fmt_on` en una pantalla de 10x4
+    // queda en las mismas celdas, y el resto no se toca
+    let mut screen = SizedBackend::<10, 4>::new();
+    write_fmt_on(&mut screen, format_args!("hello world\nok"), COLOR, 0, 0);
+    let written = screen.as_rows().iter().enumerate().all(|(y, row)| {
+        row.iter().enumerate().all(|(x, &cell)| {
+            let character = expected
+                .iter()
+                .find(|&&(_, cx, cy)| cx as usize == x && cy as usize == y)
+                .map(|&(character, _, _)| character);
+            cell == character.map_or(0, |character| make_cell(character, COLOR))
+        })
+    });
+
+    let bounds = SysPrintableChar::new(b'x', COLOR, 9, 3).is_valid_sized::<10, 4>()
+        && !SysPrintableChar::new(b'x', COLOR, 10, 0).is_valid_sized::<10, 4>()
+        && !SysPrintableChar::new(b'x', COLOR, 0, 4).is_valid_sized::<10, 4>();
+
+    matches(&wrapped, &expected)
+        && matches(&clipped, &clipped_expected)
+        && written
+        && bounds
+        && check_entry_points()
+        && check_coordinate_overflow()
+}
+
+/// Distribuye el mismo texto por cada punto de entrada de `TextLayout`
+///
+/// `new_string` y `TextCursor` deben dejar las mismas celdas en una
+/// pantalla de 10x4 con `\t`, `\r`, `\n` y wrapping. `chars_from_string`
+/// debe generar los mismos caracteres que `new_string` en la pantalla
+/// real, incluido descartar lo que queda debajo de la última fila.
+fn check_entry_points() -> bool {
+    const COLOR: u8 = 0x07;
+
+    // El tab rellena hasta la columna 4, `\r` vuelve a la 0 y la 'd'
+    // sobrescribe la 'a'
+    let sample = b"ab\tc\rd\nhello world";
+    let mut by_string = SizedBackend::<10, 4>::new();
+    by_string.write_string(sample, COLOR, 0, 0);
+    let mut by_cursor = SizedBackend::<10, 4>::new();
+    TextCursor::new(0, 0, COLOR).write_string_on(&mut by_cursor, sample);
+
+    let rows = by_string.as_rows();
+    let placed = rows[0][0] == make_cell(b'd', COLOR)
+        && rows[0][3] == make_cell(b' ', COLOR)
+        && rows[0][4] == make_cell(b'c', COLOR)
+        && rows[1][9] == make_cell(b'l', COLOR)
+        && rows[2][0] == make_cell(b'd', COLOR);
+    let same_cells = rows == by_cursor.as_rows();
+
+    // Una línea más ancha que cualquier modo y más saltos de línea que
+    // filas; la 'z' final nunca entra
+    let mut long = [b'\n'; 600];
+    long[..7].copy_from_slice(b"ab\tc\rd\n");
+    long[7..307].fill(b'x');
+    long[599] = b'z';
+    let laid_out = SysPrintableChar::new_string(&long, COLOR, 0, 0);
+    let streamed = laid_out
+        .iter()
+        .copied()
+        .eq(SysPrintableChar::chars_from_string(&long, COLOR, 0, 0));
+    let terminated = laid_out.iter().all(|syschar| syschar.character != b'z');
+
+    placed && same_cells && streamed && terminated
+}
+
+/// Distribuye textos largos desde columnas cercanas al límite de `u8`
+///
+/// Ningún carácter debe terminar en una columna menor a la inicial: con
+/// 300 bytes desde la columna 79 de una pantalla de 80x25 cada fila recibe
+/// uno en la columna 79; con un ancho mayor a 255 la distribución termina
+/// al escribir en la columna 255, y con el margen fuera de la pantalla no
+/// se escribe nada.
+fn check_coordinate_overflow() -> bool {
+    const COLOR: u8 = 0x07;
+    let long = [b'x'; 300];
+
+    let edge = SysPrintableChar::new_string_sized::<80, 25>(&long, COLOR, 79, 0);
+    let one_per_row = edge.len() == 25
+        && edge
+            .iter()
+            .enumerate()
+            .all(|(row, syschar)| syschar.x == 79 && syschar.y as usize == row);
+
+    let wide = SysPrintableChar::new_string_sized::<300, 2>(&long, COLOR, 250, 0);
+    let stopped = wide.len() == 6
+        && wide
+            .iter()
+            .all(|syschar| syschar.x >= 250 && syschar.y == 0);
+
+    let outside = SysPrintableChar::new_string_sized::<100, 4>(&long, COLOR, 200, 0);
+
+    one_per_row && stopped && outside.is_empty()
 }
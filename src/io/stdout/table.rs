@@ -0,0 +1,107 @@
+//! Renderizado de tablas de ancho fijo
+//!
+//! Permite listar datos en columnas alineadas, separadas con caracteres
+//! de dibujo de cajas.
+
+use crate::io::stdout::TextCursor;
+use crate::io::stdout::cp437::box_drawing;
+
+/// Tabla con columnas de ancho fijo
+///
+/// Cada llamada a `row` escribe una fila debajo de la anterior y `finish`
+/// cierra el borde inferior. El contenido de cada celda se alinea a la
+/// izquierda, se completa con espacios y se trunca si supera el ancho de
+/// su columna, de modo que nunca invade la columna siguiente. Una tabla
+/// de `n` filas ocupa `n + 4` filas de pantalla.
+pub struct Table<'a> {
+    widths: &'a [u8],
+    cursor: TextCursor,
+    x: u8,
+    y: u8,
+}
+
+impl<'a> Table<'a> {
+    /// Crea una tabla y dibuja el borde superior y el encabezado
+    ///
+    /// # Argumentos
+    /// * `widths` - Ancho de cada columna (sin contar los separadores)
+    /// * `header` - Títulos de las columnas
+    /// * `x`, `y` - Esquina superior izquierda de la tabla
+    /// * `color` - Color del texto y los separadores
+    pub fn new(widths: &'a [u8], header: &[&[u8]], x: u8, y: u8, color: u8) -> Self {
+        let mut table = Self {
+            widths,
+            cursor: TextCursor::new(x, y, color),
+            x,
+            y,
+        };
+
+        table.border(
+            box_drawing::TOP_LEFT,
+            box_drawing::TEE_DOWN,
+            box_drawing::TOP_RIGHT,
+        );
+        table.row(header);
+        table.separator();
+        table
+    }
+
+    /// Escribe una fila de celdas
+    ///
+    /// Las celdas que faltan se dejan vacías y las que sobran se ignoran.
+    pub fn row(&mut self, cells: &[&[u8]]) {
+        self.cursor.set_position(self.x, self.y);
+        self.cursor.write_char(box_drawing::VERTICAL);
+
+        for (column, &width) in self.widths.iter().enumerate() {
+            let cell = cells.get(column).copied().unwrap_or(b"");
+
+            for offset in 0..width as usize {
+                self.cursor
+                    .write_char(cell.get(offset).copied().unwrap_or(b' '));
+            }
+            self.cursor.write_char(box_drawing::VERTICAL);
+        }
+
+        self.y += 1;
+    }
+
+    /// Dibuja una línea horizontal de separación entre filas
+    pub fn separator(&mut self) {
+        self.border(
+            box_drawing::TEE_RIGHT,
+            box_drawing::CROSS,
+            box_drawing::TEE_LEFT,
+        );
+    }
+
+    /// Dibuja el borde inferior; la tabla no admite más filas después
+    pub fn finish(mut self) {
+        self.border(
+            box_drawing::BOTTOM_LEFT,
+            box_drawing::TEE_UP,
+            box_drawing::BOTTOM_RIGHT,
+        );
+    }
+
+    /// Dibuja una línea horizontal con los extremos y uniones indicados
+    fn border(&mut self, left: u8, joint: u8, right: u8) {
+        self.cursor.set_position(self.x, self.y);
+        self.cursor.write_char(left);
+
+        for (column, &width) in self.widths.iter().enumerate() {
+            for _ in 0..width {
+                self.cursor.write_char(box_drawing::HORIZONTAL);
+            }
+
+            let end = if column + 1 == self.widths.len() {
+                right
+            } else {
+                joint
+            };
+            self.cursor.write_char(end);
+        }
+
+        self.y += 1;
+    }
+}
@@ -0,0 +1,124 @@
+//! Temas de color de la consola
+//!
+//! Un `Theme` agrupa los colores que usa la salida sin color explícito:
+//! el texto por defecto, el registro (`log`) y la línea de estado. Cambiar
+//! de tema cambia la apariencia de toda la consola de una vez.
+
+use crate::io::stdout::colors::{
+    BLACK, BROWN, GREEN, LIGHT_GRAY, LIGHT_GREEN, LIGHT_RED, RED, WHITE, YELLOW, make_color,
+};
+use crate::io::stdout::{WRITER, buffer_mut, set_default_color};
+use crate::sync::{SpinLock, without_interrupts};
+
+/// Colores de la consola (índices 0x0-0xF)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Color del texto normal
+    pub foreground: u8,
+    /// Fondo de toda la salida
+    pub background: u8,
+    /// Resaltado: mensajes de información y fondo de la línea de estado
+    pub accent: u8,
+    /// Mensajes de error
+    pub error: u8,
+    /// Advertencias
+    pub warning: u8,
+}
+
+impl Theme {
+    /// Gris claro sobre negro, los colores del BIOS
+    pub const fn classic() -> Self {
+        Self {
+            foreground: LIGHT_GRAY,
+            background: BLACK,
+            accent: GREEN,
+            error: RED,
+            warning: YELLOW,
+        }
+    }
+
+    /// Verde sobre negro, como un monitor de fósforo verde
+    pub const fn matrix() -> Self {
+        Self {
+            foreground: GREEN,
+            background: BLACK,
+            accent: LIGHT_GREEN,
+            error: LIGHT_RED,
+            warning: WHITE,
+        }
+    }
+
+    /// Ámbar sobre negro, como un monitor de fósforo ámbar
+    pub const fn amber() -> Self {
+        Self {
+            foreground: BROWN,
+            background: BLACK,
+            accent: YELLOW,
+            error: LIGHT_RED,
+            warning: WHITE,
+        }
+    }
+
+    /// Byte de color del texto normal
+    pub const fn text_color(&self) -> u8 {
+        make_color(self.foreground, self.background)
+    }
+
+    /// Byte de color del texto resaltado
+    pub const fn accent_color(&self) -> u8 {
+        make_color(self.accent, self.background)
+    }
+
+    /// Byte de color de los errores
+    pub const fn error_color(&self) -> u8 {
+        make_color(self.error, self.background)
+    }
+
+    /// Byte de color de las advertencias
+    pub const fn warning_color(&self) -> u8 {
+        make_color(self.warning, self.background)
+    }
+
+    /// Byte de color de la línea de estado (fondo del color de acento)
+    pub const fn status_color(&self) -> u8 {
+        make_color(self.background, self.accent)
+    }
+}
+
+static THEME: SpinLock<Theme> = SpinLock::new(Theme::classic());
+
+/// Tema activo
+pub fn theme() -> Theme {
+    *THEME.lock()
+}
+
+/// Activa un tema
+///
+/// Cambia también el color por defecto (`set_default_color`) y el del
+/// writer global, así que la salida siguiente ya usa el tema. Lo que ya
+/// está en pantalla conserva sus colores; para repintarlo se usa
+/// `repaint`.
+pub fn set_theme(theme: Theme) {
+    *THEME.lock() = theme;
+
+    let color = theme.text_color();
+    set_default_color(color);
+    without_interrupts(|| WRITER.lock().set_color(color));
+}
+
+/// Pinta toda la pantalla con el color de texto del tema activo
+///
+/// Conserva los caracteres y reemplaza el color de cada celda, de modo
+/// que el contenido actual queda con el fondo del tema.
+pub fn repaint() {
+    let color = (theme().text_color() as u16) << 8;
+
+    // Sin interrupciones nadie más escribe en pantalla mientras existe el
+    // slice
+    without_interrupts(|| {
+        let cells = unsafe { buffer_mut() };
+        for cell in cells {
+            *cell = *cell & 0x00FF | color;
+        }
+    });
+}
@@ -0,0 +1,103 @@
+//! Terminales virtuales
+//!
+//! Varias pantallas independientes que comparten el buffer VGA, al estilo
+//! de las consolas de Linux con Alt+F1..F6. Solo la terminal activa está
+//! en pantalla; las demás guardan su contenido y la posición del cursor en
+//! su propio buffer hasta que se vuelve a ellas con `switch_vt`.
+
+use crate::io::stdout::colors::LIGHT_GRAY;
+use crate::io::stdout::{
+    VGA_MAX_CELLS, WRITER, make_cell, restore_screen, save_screen, set_cursor_position,
+};
+use crate::sync::{SpinLock, without_interrupts};
+
+/// Cantidad de terminales virtuales
+///
+/// Cada una guarda una pantalla del modo más grande, así que se mantiene
+/// chica.
+pub const VT_COUNT: usize = 3;
+
+/// Una terminal virtual: contenido de pantalla y posición del cursor
+pub struct Vt {
+    cells: [u16; VGA_MAX_CELLS],
+    cursor: (u8, u8),
+}
+
+impl Vt {
+    /// Crea una terminal vacía con el cursor en la esquina superior
+    /// izquierda
+    pub const fn new() -> Self {
+        Self {
+            cells: [make_cell(b' ', LIGHT_GRAY); VGA_MAX_CELLS],
+            cursor: (0, 0),
+        }
+    }
+}
+
+/// Terminales y cuál de ellas está en pantalla
+struct Terminals {
+    vts: [Vt; VT_COUNT],
+    active: usize,
+}
+
+static TERMINALS: SpinLock<Terminals> = SpinLock::new(Terminals {
+    vts: [const { Vt::new() }; VT_COUNT],
+    active: 0,
+});
+
+/// Índice (desde 0) de la terminal en pantalla
+pub fn active_vt() -> usize {
+    TERMINALS.lock().active
+}
+
+/// Cambia a la terminal `n` (desde 0)
+///
+/// Guarda la pantalla y la posición del writer global en la terminal
+/// saliente y muestra la entrante, restaurando su cursor. Cambiar a la
+/// terminal activa no hace nada.
+///
+/// # Retorna
+/// `false` si `n` no es una terminal válida
+pub fn switch_vt(n: usize) -> bool {
+    if n >= VT_COUNT {
+        return false;
+    }
+
+    without_interrupts(|| {
+        let mut terminals = TERMINALS.lock();
+        let active = terminals.active;
+        if n == active {
+            return;
+        }
+
+        let mut writer = WRITER.lock();
+        let outgoing = &mut terminals.vts[active];
+        save_screen(&mut outgoing.cells);
+        outgoing.cursor = writer.position();
+
+        let incoming = &terminals.vts[n];
+        restore_screen(&incoming.cells);
+        let (x, y) = incoming.cursor;
+        writer.set_position(x, y);
+        set_cursor_position(x, y);
+
+        terminals.active = n;
+    });
+    true
+}
+
+/// Handlers para cambiar a cada terminal, en orden
+///
+/// Pensados para registrarse con `keyboard::on_alt_function_key`, de modo
+/// que Alt+F1 muestre la primera terminal, Alt+F2 la segunda, etc.
+pub const SWITCH_HANDLERS: [fn(); VT_COUNT] = [
+    || {
+        switch_vt(0);
+    },
+    || {
+        switch_vt(1);
+    },
+    || {
+        switch_vt(2);
+    },
+];
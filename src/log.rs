@@ -0,0 +1,202 @@
+//! Registro de mensajes del kernel
+//!
+//! Los mensajes emitidos durante la inicialización, antes de que la
+//! pantalla o el puerto serie estén configurados, se guardan en un ring
+//! buffer en memoria. Cuando la salida está disponible, `flush_to_screen`
+//! y `flush_to_serial` vuelcan el registro completo, y después de
+//! `enable_direct_output` los mensajes nuevos se escriben directamente en
+//! el writer global y por serie, con el prefijo del nivel en color.
+//!
+//! ```ignore
+//! log::info!("timer listo");
+//! log::error!("teclado: {:?}", error);
+//! ```
+
+use crate::io::serial;
+use crate::io::stdout::{TextCursor, WRITER, default_color, scrollback, theme};
+use crate::sync::{SpinLock, without_interrupts};
+use core::fmt::{self, Write};
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use heapless::{Deque, String};
+
+/// Cantidad de líneas que guarda el registro (se descartan las más viejas)
+pub const LOG_CAPACITY: usize = 32;
+
+/// Largo máximo de cada línea; el texto que sobra se trunca
+pub const LINE_LENGTH: usize = 72;
+
+/// Nivel de un mensaje
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// Prefijo mostrado antes del mensaje
+    pub const fn prefix(self) -> &'static str {
+        match self {
+            Level::Info => "[INFO] ",
+            Level::Warn => "[WARN] ",
+            Level::Error => "[ERROR] ",
+        }
+    }
+
+    /// Color usado al volcar el mensaje en pantalla, según el tema activo
+    pub fn color(self) -> u8 {
+        let theme = theme::theme();
+        match self {
+            Level::Info => theme.accent_color(),
+            Level::Warn => theme.warning_color(),
+            Level::Error => theme.error_color(),
+        }
+    }
+}
+
+/// Línea guardada en el registro
+struct Entry {
+    level: Level,
+    text: String<LINE_LENGTH>,
+}
+
+static LOG: SpinLock<Deque<Entry, LOG_CAPACITY>> = SpinLock::new(Deque::new());
+
+/// Nivel mínimo de los mensajes que se registran
+static MIN_LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/// Si es verdadero, los mensajes se escriben en lugar de guardarse
+static DIRECT_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Descarta los mensajes de nivel menor a `level`
+///
+/// Por ejemplo, `set_log_level(Level::Warn)` oculta los mensajes Info.
+pub fn set_log_level(level: Level) {
+    MIN_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Escribe los mensajes nuevos directamente en pantalla y por serie
+///
+/// Hasta que se llama, los mensajes solo se guardan en el registro. El
+/// puerto serie debe estar inicializado.
+pub fn enable_direct_output() {
+    DIRECT_OUTPUT.store(true, Ordering::Relaxed);
+}
+
+/// Agrega un mensaje al registro, o lo escribe si ya hay salida
+///
+/// Usado por las macros `info!`, `warn!` y `error!` de este módulo. En el
+/// registro el mensaje se trunca a `LINE_LENGTH` bytes; la escritura
+/// directa formatea sin buffers intermedios, así que no trunca.
+pub fn record(level: Level, args: fmt::Arguments) {
+    if (level as u8) < MIN_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+
+    if DIRECT_OUTPUT.load(Ordering::Relaxed) {
+        without_interrupts(|| write_line(&mut WRITER.lock(), level, args));
+        serial::write_fmt(format_args!("{}{}\n", level.prefix(), args));
+        return;
+    }
+
+    let mut text = String::new();
+    let _ = text.write_fmt(args);
+
+    without_interrupts(|| {
+        let mut log = LOG.lock();
+        if log.is_full() {
+            log.pop_front();
+        }
+        let _ = log.push_back(Entry { level, text });
+    });
+}
+
+/// Escribe una línea con el prefijo del nivel en color y el mensaje en el
+/// color por defecto
+fn write_line(writer: &mut TextCursor, level: Level, args: fmt::Arguments) {
+    writer.set_color(level.color());
+    let _ = writer.write_str(level.prefix());
+    writer.set_color(default_color());
+    let _ = writer.write_fmt(args);
+    let _ = writer.write_str("\n");
+
+    scrollback::record(level.prefix().as_bytes());
+    scrollback::record_fmt(format_args!("{}\n", args));
+}
+
+/// Escribe el registro completo en el writer global, con color por nivel
+///
+/// El registro no se vacía, así que puede volcarse también por serie.
+pub fn flush_to_screen() {
+    without_interrupts(|| {
+        let log = LOG.lock();
+        let mut writer = WRITER.lock();
+
+        for entry in log.iter() {
+            write_line(&mut writer, entry.level, format_args!("{}", entry.text));
+        }
+    });
+}
+
+/// Escribe el registro completo por el puerto serie
+///
+/// El puerto debe estar inicializado con `serial::init`.
+pub fn flush_to_serial() {
+    let log = LOG.lock();
+
+    for entry in log.iter() {
+        serial::write_fmt(format_args!("{}{}\n", entry.level.prefix(), entry.text));
+    }
+}
+
+/// Registra o escribe un mensaje de nivel Info
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::log::record($crate::log::Level::Info, format_args!($($arg)*))
+    };
+}
+
+/// Registra o escribe un mensaje de nivel Warn
+macro_rules! log_warn {
+    ($($arg:tt)*) => {
+        $crate::log::record($crate::log::Level::Warn, format_args!($($arg)*))
+    };
+}
+
+/// Registra o escribe un mensaje de nivel Error
+macro_rules! error {
+    ($($arg:tt)*) => {
+        $crate::log::record($crate::log::Level::Error, format_args!($($arg)*))
+    };
+}
+
+/// Verifica una condición sin detener el kernel
+///
+/// Si la condición es falsa registra un mensaje de nivel Error (en rojo)
+/// con el archivo y la línea, igual que el panic handler, y continúa. Sin
+/// mensaje se muestra la condición tal como está escrita. La expresión
+/// vale el resultado de la condición, para poder reaccionar al fallo.
+///
+/// ```ignore
+/// soft_assert!(x < width(), "cursor out of bounds: {}", x);
+/// ```
+macro_rules! soft_assert {
+    ($cond:expr $(,)?) => {
+        $crate::log::soft_assert!($cond, "{}", stringify!($cond))
+    };
+    ($cond:expr, $($arg:tt)+) => {{
+        let ok: bool = $cond;
+        if !ok {
+            $crate::log::error!(
+                "assertion failed: {} at {}:{}",
+                format_args!($($arg)+),
+                file!(),
+                line!()
+            );
+        }
+        ok
+    }};
+}
+
+pub(crate) use {error, info, log_warn as warn, soft_assert};
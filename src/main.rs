@@ -1,19 +1,72 @@
 #![no_std]
 #![no_main]
 
+#[cfg(feature = "bench")]
+mod bench;
+mod clock;
+mod cmos;
+mod cpu;
+mod demo;
+mod event;
+mod gdt;
 mod io;
+mod log;
+mod memory;
+mod rtc;
+mod shell;
+mod sync;
+mod system;
+mod task;
+mod timer;
 
-use crate::io::stdout::{VGA_WIDTH, colors::LIGHT_GRAY, structs::SysPrintableChar};
 use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Indica que el kernel ya está dentro del panic handler
+static IN_PANIC: AtomicBool = AtomicBool::new(false);
+
+/// Escribe "DOUBLE PANIC" directo al buffer VGA y detiene la CPU
+///
+/// Se usa cuando el panic handler entra en panic: no formatea ni toma
+/// locks, para que nada de lo que falló la primera vez vuelva a ejecutarse.
+fn double_panic() -> ! {
+    const MESSAGE: &[u8] = b"DOUBLE PANIC";
+    const COLOR: u8 = 0x4F; // Blanco sobre rojo
+
+    let base = io::stdout::vga_base() as *mut u16;
+    for (index, &byte) in MESSAGE.iter().enumerate() {
+        unsafe {
+            core::ptr::write_volatile(base.add(index), (COLOR as u16) << 8 | byte as u16);
+        }
+    }
+
+    io::stdout::flush();
+
+    loop {
+        unsafe {
+            core::arch::asm!("cli", "hlt", options(nomem, nostack));
+        }
+    }
+}
 
 /// Maneja los panics del kernel de forma segura y con información clara
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
     use crate::io::stdout::colors::RED;
     use crate::io::stdout::{self, structs::SysPrintableChar};
-    use core::fmt::Write;
     use heapless::Vec;
-    use itoa::Buffer;
+
+    // Un panic dentro del handler (al formatear o escribir) terminaría en
+    // recursión; en ese caso solo se muestra un mensaje mínimo.
+    if IN_PANIC.swap(true, Ordering::SeqCst) {
+        double_panic();
+    }
+
+    // Liberar el writer global por si el panic ocurrió mientras estaba
+    // tomado; el kernel se detiene después, así que nadie más lo usará.
+    unsafe {
+        stdout::WRITER.force_unlock();
+    }
 
     // Buffer para el mensaje de panic
     let mut buffer: Vec<SysPrintableChar, 512> = Vec::new();
@@ -22,26 +75,28 @@ fn panic(info: &PanicInfo) -> ! {
     let panic_title = SysPrintableChar::new_string(b"[KERNEL PANIC]", RED, 1, 0);
     let _ = buffer.extend(panic_title.iter().cloned());
 
-    // Mensaje del panic
-    if let Some(message) = info.payload().downcast_ref::<&str>() {
-        let msg_chars = SysPrintableChar::new_string(message.as_bytes(), RED, 1, 1);
-        let _ = buffer.extend(msg_chars.iter().cloned());
-    } else {
-        let generic_msg = SysPrintableChar::new_string(b"Unknown panic occurred", RED, 1, 1);
-        let _ = buffer.extend(generic_msg.iter().cloned());
-    }
+    // Escribir todo al buffer VGA
+    stdout::write_buffer(buffer);
+
+    // Mensaje del panic, formateado con sus argumentos
+    stdout::write_fmt_at(format_args!("{}", info.message()), RED, 1, 1);
 
     // Información de ubicación si está disponible
     if let Some(location) = info.location() {
-        let mut line_info: heapless::String<64> = heapless::String::new();
-        let _ = write!(line_info, "at {}:{}", location.file(), location.line());
-
-        let location_chars = SysPrintableChar::new_string(line_info.as_bytes(), RED, 1, 2);
-        let _ = buffer.extend(location_chars.iter().cloned());
+        stdout::write_fmt_at(
+            format_args!("at {}:{}", location.file(), location.line()),
+            RED,
+            1,
+            2,
+        );
     }
+    stdout::flush();
 
-    // Escribir todo al buffer VGA
-    stdout::write_buffer(buffer);
+    match system::panic_action() {
+        system::PanicAction::Halt => {}
+        system::PanicAction::Reboot => system::reboot(),
+        system::PanicAction::TripleFault => system::triple_fault(),
+    }
 
     // Loop infinito para detener el kernel
     loop {
@@ -49,83 +104,64 @@ fn panic(info: &PanicInfo) -> ! {
     }
 }
 
-/// Estructura para manejar el estado del cursor
-#[derive(Debug, Clone, Copy)]
-struct CursorPosition {
-    x: usize,
-    y: usize,
-}
-
-impl CursorPosition {
-    const fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
+/// Punto de entrada principal del kernel
+#[unsafe(no_mangle)]
+pub extern "C" fn _start() -> ! {
+    // Reemplazar la GDT del bootloader por la del kernel
+    gdt::init();
+
+    // Detectar el buffer de texto (color o monocromo)
+    io::stdout::init_vga();
+    log::info!("VGA text buffer at {:p}", io::stdout::vga_base());
+    if !io::stdout::backend::self_check() {
+        log::error!("VGA backend self-check failed");
     }
-
-    fn move_to_next_line(&mut self) {
-        self.y += 1;
-        self.x = 0;
-
-        // Mantener el cursor dentro de los límites de la pantalla
-        if self.y >= 25 {
-            self.y = 24;
-        }
+    if !io::stdout::structs::layout_self_check() {
+        log::error!("Text layout self-check failed");
     }
 
-    fn advance_cursor(&mut self) {
-        self.x += 1;
+    log::info!("Memory: {} KiB (CMOS)", memory::detect_basic());
 
-        // Wrap a la siguiente línea si es necesario
-        if self.x >= VGA_WIDTH {
-            self.move_to_next_line();
-        }
+    // Inicializar el temporizador (antes del teclado, para que las esperas
+    // al controlador se midan en milisegundos)
+    timer::init();
+    log::info!("PIT timer ready");
+
+    // Inicializar el teclado
+    match io::stdin::keyboard::init_keyboard() {
+        Ok(()) => log::info!("Keyboard ready"),
+        Err(error) => log::error!("Keyboard init failed: {:?}", error),
     }
 
-    fn is_within_bounds(&self) -> bool {
-        self.x < VGA_WIDTH && self.y < 25
+    // Inicializar el mouse PS/2 (puerto auxiliar del mismo controlador)
+    match io::mouse::init() {
+        Ok(()) => log::info!("Mouse ready"),
+        Err(error) => log::warn!("Mouse init failed: {:?}", error),
     }
-}
 
-/// Maneja la entrada de caracteres del teclado
-fn handle_character_input(character: char, cursor: &mut CursorPosition) {
-    match character {
-        '\n' => {
-            cursor.move_to_next_line();
-        }
+    // F2 inicia el demo de snake
+    io::stdin::keyboard::on_function_key(2, demo::run);
 
-        c if c.is_ascii() && c.is_control() == false => {
-            // Solo caracteres ASCII normales (no de control)
-            if cursor.is_within_bounds() {
-                let display_char =
-                    SysPrintableChar::new(c as u8, LIGHT_GRAY, cursor.x as u8, cursor.y as u8);
-                io::stdout::write_char_at(display_char);
-                cursor.advance_cursor();
-            }
-        }
-        _ => {
-            // Ignorar caracteres especiales o no ASCII
-        }
+    // Alt+F1..F3 cambian de terminal virtual
+    for (index, &handler) in io::stdout::vt::SWITCH_HANDLERS.iter().enumerate() {
+        io::stdin::keyboard::on_alt_function_key(index as u8 + 1, handler);
     }
-}
 
-/// Punto de entrada principal del kernel
-#[unsafe(no_mangle)]
-pub extern "C" fn _start() -> ! {
-    // Inicializar el teclado
-    io::stdin::keyboard::init_keyboard();
+    // Volcar el registro de arranque ahora que hay salida disponible
+    io::serial::init();
+    io::output::enable(io::output::OutputSink::Serial);
+    log::flush_to_serial();
+    log::flush_to_screen();
+    log::enable_direct_output();
 
-    // Configurar la pantalla inicial
-    let mut cursor = CursorPosition::new(1, 1);
+    #[cfg(feature = "bench")]
+    bench::run();
 
-    // Loop principal del kernel
-    loop {
-        // Polling del teclado
-        if let Some(character) = io::stdin::keyboard::poll_keyboard() {
-            handle_character_input(character, &mut cursor);
-        }
-
-        // Pequeña pausa para no sobrecargar la CPU
-        for _ in 0..10000 {
-            core::hint::spin_loop();
-        }
+    // Hora en la esquina superior derecha
+    if !clock::start() {
+        log::warn!("Clock task not started: task table full");
     }
+
+    // Ceder el control al shell, debajo del registro de arranque
+    shell::run()
 }
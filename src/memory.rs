@@ -0,0 +1,46 @@
+//! Detección de la memoria disponible
+//!
+//! Por ahora solo se consulta lo que el BIOS deja en la CMOS, que alcanza
+//! para una cota aproximada hasta tener un parser de multiboot.
+
+use crate::cmos;
+
+/// Registros de la CMOS con la memoria extendida (KiB sobre 1 MiB)
+mod registers {
+    /// Valor medido por el POST, byte bajo/alto
+    pub const EXTENDED_LOW: u8 = 0x30;
+    pub const EXTENDED_HIGH: u8 = 0x31;
+    /// Valor configurado en el setup del BIOS, byte bajo/alto
+    pub const EXTENDED_SETUP_LOW: u8 = 0x17;
+    pub const EXTENDED_SETUP_HIGH: u8 = 0x18;
+}
+
+/// Memoria por debajo de 1 MiB, que la CMOS no cuenta como extendida
+const FIRST_MEBIBYTE_KB: u32 = 1024;
+
+/// Lee un valor de 16 bits de dos registros de la CMOS
+fn read_u16(low: u8, high: u8) -> u16 {
+    u16::from_le_bytes([cmos::read_register(low), cmos::read_register(high)])
+}
+
+/// Estima la RAM instalada a partir de la CMOS
+///
+/// Suma el primer MiB a la memoria extendida que informa el BIOS. Usa el
+/// valor medido por el POST (registros 0x30/0x31) y, si está en cero, el
+/// del setup (0x17/0x18). Los registros son de 16 bits, así que con más
+/// de ~64 MiB el resultado queda en ese tope: sirve como cota inferior,
+/// no como mapa de memoria.
+///
+/// # Retorna
+/// La memoria estimada en KiB
+pub fn detect_basic() -> u32 {
+    let mut extended = read_u16(registers::EXTENDED_LOW, registers::EXTENDED_HIGH);
+    if extended == 0 {
+        extended = read_u16(
+            registers::EXTENDED_SETUP_LOW,
+            registers::EXTENDED_SETUP_HIGH,
+        );
+    }
+
+    FIRST_MEBIBYTE_KB + extended as u32
+}
@@ -0,0 +1,94 @@
+//! Reloj de tiempo real (RTC) de la CMOS
+//!
+//! El RTC sigue contando con la máquina apagada. Sus registros se leen con
+//! `cmos::read_register`; según el registro de estado B pueden estar en
+//! BCD o en binario y la hora en formato de 12 o 24 horas.
+
+use crate::cmos;
+
+/// Registros del RTC
+mod registers {
+    pub const SECONDS: u8 = 0x00;
+    pub const MINUTES: u8 = 0x02;
+    pub const HOURS: u8 = 0x04;
+    pub const STATUS_A: u8 = 0x0A;
+    pub const STATUS_B: u8 = 0x0B;
+}
+
+/// Bit del estado A: el RTC está actualizando sus registros
+const UPDATE_IN_PROGRESS: u8 = 0x80;
+
+/// Bits del estado B
+const STATUS_B_24_HOUR: u8 = 0x02;
+const STATUS_B_BINARY: u8 = 0x04;
+
+/// Bit de la hora que indica PM en formato de 12 horas
+const HOUR_PM: u8 = 0x80;
+
+/// Hora del día leída del RTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    /// Hora en formato de 24 horas (0-23)
+    pub hours: u8,
+    pub minutes: u8,
+    pub seconds: u8,
+}
+
+/// Lee los registros de hora tal como están, sin convertir
+fn read_raw() -> (u8, u8, u8) {
+    while cmos::read_register(registers::STATUS_A) & UPDATE_IN_PROGRESS != 0 {
+        core::hint::spin_loop();
+    }
+
+    (
+        cmos::read_register(registers::HOURS),
+        cmos::read_register(registers::MINUTES),
+        cmos::read_register(registers::SECONDS),
+    )
+}
+
+/// Convierte un valor BCD (dos dígitos) a binario
+const fn from_bcd(value: u8) -> u8 {
+    (value >> 4) * 10 + (value & 0x0F)
+}
+
+/// Lee la hora actual del RTC
+///
+/// Espera a que el RTC no esté actualizando y repite la lectura hasta
+/// obtener dos iguales seguidas, para no combinar valores de antes y
+/// después de una actualización.
+pub fn read_time() -> Time {
+    let mut raw = read_raw();
+    loop {
+        let again = read_raw();
+        if again == raw {
+            break;
+        }
+        raw = again;
+    }
+
+    let (hour_byte, minutes, seconds) = raw;
+    let status_b = cmos::read_register(registers::STATUS_B);
+    let pm = hour_byte & HOUR_PM != 0;
+    let hours = hour_byte & !HOUR_PM;
+
+    let (mut hours, minutes, seconds) = if status_b & STATUS_B_BINARY == 0 {
+        (from_bcd(hours), from_bcd(minutes), from_bcd(seconds))
+    } else {
+        (hours, minutes, seconds)
+    };
+
+    // En formato de 12 horas las horas van de 1 a 12
+    if status_b & STATUS_B_24_HOUR == 0 {
+        hours %= 12;
+        if pm {
+            hours += 12;
+        }
+    }
+
+    Time {
+        hours,
+        minutes,
+        seconds,
+    }
+}
@@ -0,0 +1,225 @@
+//! Shell de comandos del kernel
+//!
+//! Muestra un prompt, edita la línea con `LineEditor` y, al presionar
+//! Enter, separa la línea en palabras y ejecuta el comando de la tabla
+//! `COMMANDS` cuyo nombre coincide con la primera.
+
+use crate::event::{Event, EventLoop};
+use crate::io::stdin::keyboard::{self, KeyEvent};
+use crate::io::stdin::line::LineEditor;
+use crate::io::stdout::colors::{self, is_valid_color, make_color, with_foreground};
+use crate::io::stdout::{self, WRITER};
+use crate::sync::{SpinLock, without_interrupts};
+use crate::{kprint, kprintln, print, println, task};
+use heapless::Vec;
+
+/// Texto del prompt
+const PROMPT: &str = "> ";
+
+/// Largo máximo de una línea de comando
+const LINE_CAPACITY: usize = 76;
+
+/// Cantidad máxima de palabras en una línea (comando incluido)
+const MAX_ARGS: usize = 8;
+
+/// Intervalo entre las ejecuciones de las tareas en milisegundos
+const LOOP_INTERVAL_MS: u64 = 16;
+
+/// Comando del shell
+struct Command {
+    name: &'static [u8],
+    usage: &'static str,
+    run: fn(&[&[u8]]),
+}
+
+/// Comandos disponibles, en el orden en que los lista `help`
+const COMMANDS: &[Command] = &[
+    Command {
+        name: b"help",
+        usage: "help               list commands",
+        run: help,
+    },
+    Command {
+        name: b"clear",
+        usage: "clear              clear the screen",
+        run: clear,
+    },
+    Command {
+        name: b"color",
+        usage: "color <fg> [bg]    set the text color (0-15)",
+        run: color,
+    },
+    Command {
+        name: b"theme",
+        usage: "theme <name>       classic, matrix or amber",
+        run: theme,
+    },
+    Command {
+        name: b"echo",
+        usage: "echo <text...>     print the arguments",
+        run: echo,
+    },
+    Command {
+        name: b"pattern",
+        usage: "pattern            show a color test pattern",
+        run: pattern,
+    },
+];
+
+static EDITOR: SpinLock<LineEditor<LINE_CAPACITY>> =
+    SpinLock::new(LineEditor::new(stdout::colors::LIGHT_GRAY));
+
+/// Inicia el shell y se queda atendiendo el teclado
+///
+/// Reemplaza al loop principal: atiende las teclas a medida que llegan y
+/// ejecuta las tareas cooperativas en cada `Event::Tick`. Mientras se
+/// imprime la salida de un comando, el cursor de hardware se oculta para
+/// que no salte por la pantalla.
+pub fn run() -> ! {
+    stdout::set_cursor_blink(false);
+    prompt();
+
+    // Con el handler instalado, `EventLoop` entrega las teclas a
+    // `handle_key`; un programa modal puede reemplazarlo y devolverlo
+    keyboard::set_input_handler(handle_key);
+
+    let mut events = EventLoop::new(LOOP_INTERVAL_MS);
+    loop {
+        match events.next_event() {
+            Event::Key(event) => handle_key(event),
+            Event::Tick => task::run_tasks(),
+        }
+    }
+}
+
+/// Muestra el prompt resaltado y empieza una línea nueva después de él
+fn prompt() {
+    let color = stdout::default_color();
+    without_interrupts(|| WRITER.lock().set_color(colors::emphasize(color)));
+    print!("{}", PROMPT);
+    without_interrupts(|| WRITER.lock().set_color(color));
+
+    let (x, y) = without_interrupts(|| WRITER.lock().position());
+    let mut editor = EDITOR.lock();
+    editor.set_color(color);
+    editor.begin(x, y);
+}
+
+/// Handler de teclado del shell
+fn handle_key(event: KeyEvent) {
+    let mut line: Vec<u8, LINE_CAPACITY> = Vec::new();
+    {
+        let mut editor = EDITOR.lock();
+        if !editor.handle(event) {
+            return;
+        }
+        let _ = line.extend_from_slice(editor.line());
+        stdout::scrollback::record(&line);
+
+        // Dejar el writer después de la línea para que la salida siga debajo
+        let (x, y) = editor.end_position();
+        without_interrupts(|| WRITER.lock().set_position(x, y));
+    }
+
+    println!();
+    execute(&line);
+    prompt();
+}
+
+/// Separa una línea en palabras y ejecuta el comando
+fn execute(line: &[u8]) {
+    let mut words: Vec<&[u8], MAX_ARGS> = Vec::new();
+    for word in line
+        .split(|&byte| byte == b' ')
+        .filter(|word| !word.is_empty())
+    {
+        if words.push(word).is_err() {
+            println!("too many arguments");
+            return;
+        }
+    }
+
+    let Some((&name, args)) = words.split_first() else {
+        return;
+    };
+
+    match COMMANDS.iter().find(|command| command.name == name) {
+        Some(command) => (command.run)(args),
+        None => println!(
+            "unknown command: {}",
+            core::str::from_utf8(name).unwrap_or("?")
+        ),
+    }
+}
+
+/// Interpreta un número de color en decimal (0-15)
+fn parse_color(arg: &[u8]) -> Option<u8> {
+    let value = core::str::from_utf8(arg).ok()?.parse::<u8>().ok()?;
+    is_valid_color(value).then_some(value)
+}
+
+fn help(_args: &[&[u8]]) {
+    for command in COMMANDS {
+        println!("{}", command.usage);
+    }
+}
+
+fn clear(_args: &[&[u8]]) {
+    stdout::clear_screen(stdout::default_color());
+    stdout::scrollback::clear();
+    without_interrupts(|| WRITER.lock().set_position(0, 0));
+}
+
+fn color(args: &[&[u8]]) {
+    let color = match args {
+        [fg] => parse_color(fg).map(|fg| with_foreground(stdout::default_color(), fg)),
+        [fg, bg] => parse_color(fg)
+            .zip(parse_color(bg))
+            .map(|(fg, bg)| make_color(fg, bg)),
+        _ => {
+            println!("usage: color <fg> [bg]");
+            return;
+        }
+    };
+    let Some(color) = color else {
+        println!("colors must be numbers from 0 to 15");
+        return;
+    };
+
+    stdout::set_default_color(color);
+    without_interrupts(|| WRITER.lock().set_color(color));
+}
+
+fn theme(args: &[&[u8]]) {
+    let theme = match args {
+        [b"classic"] => stdout::Theme::classic(),
+        [b"matrix"] => stdout::Theme::matrix(),
+        [b"amber"] => stdout::Theme::amber(),
+        _ => {
+            println!("usage: theme <classic|matrix|amber>");
+            return;
+        }
+    };
+
+    stdout::set_theme(theme);
+    stdout::theme::repaint();
+}
+
+/// Muestra el patrón de prueba hasta que se presiona una tecla
+fn pattern(args: &[&[u8]]) {
+    stdout::draw_test_pattern();
+    while keyboard::poll_keyboard_event().is_none() {
+        core::hint::spin_loop();
+    }
+    clear(args);
+}
+
+fn echo(args: &[&[u8]]) {
+    for (index, arg) in args.iter().enumerate() {
+        if index > 0 {
+            kprint!(" ");
+        }
+        kprint!("{}", core::str::from_utf8(arg).unwrap_or("?"));
+    }
+    kprintln!();
+}
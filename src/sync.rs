@@ -0,0 +1,115 @@
+//! Primitivas de sincronización del kernel
+//!
+//! Proporciona un spinlock mínimo basado en `AtomicBool` y utilidades para
+//! ejecutar código con las interrupciones deshabilitadas.
+
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Spinlock que protege un valor compartido
+///
+/// Mientras el lock está tomado, cualquier otro intento de tomarlo gira
+/// en un loop hasta que se libere. No hay colas ni prioridades: está
+/// pensado para secciones críticas cortas como escribir en pantalla.
+pub struct SpinLock<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+// El acceso a `data` está serializado por `locked`
+unsafe impl<T: Send> Sync for SpinLock<T> {}
+
+impl<T> SpinLock<T> {
+    /// Crea un nuevo spinlock liberado
+    pub const fn new(data: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    /// Toma el lock, girando hasta que esté disponible
+    pub fn lock(&self) -> SpinLockGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+
+            while self.locked.load(Ordering::Relaxed) {
+                core::hint::spin_loop();
+            }
+        }
+    }
+
+    /// Intenta tomar el lock sin bloquear
+    ///
+    /// # Retorna
+    /// `None` si el lock ya está tomado
+    pub fn try_lock(&self) -> Option<SpinLockGuard<'_, T>> {
+        self.locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| SpinLockGuard { lock: self })
+    }
+
+    /// Libera el lock sin importar quién lo tenga
+    ///
+    /// # Seguridad
+    /// Solo debe usarse cuando el dueño actual del lock nunca volverá a
+    /// ejecutarse, por ejemplo en el panic handler justo antes de detener
+    /// el kernel. En cualquier otro caso rompe la exclusión mutua.
+    pub unsafe fn force_unlock(&self) {
+        self.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Guard que mantiene el lock tomado mientras existe
+pub struct SpinLockGuard<'a, T> {
+    lock: &'a SpinLock<T>,
+}
+
+impl<T> Deref for SpinLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.data.get() }
+    }
+}
+
+impl<T> DerefMut for SpinLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.data.get() }
+    }
+}
+
+impl<T> Drop for SpinLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+/// Bit de interrupciones habilitadas (IF) en RFLAGS
+const RFLAGS_INTERRUPT_FLAG: u64 = 1 << 9;
+
+/// Ejecuta `f` con las interrupciones deshabilitadas
+///
+/// Al terminar, el estado de las interrupciones se restaura al que tenía
+/// antes de la llamada, por lo que es seguro anidar llamadas.
+pub fn without_interrupts<R>(f: impl FnOnce() -> R) -> R {
+    let rflags: u64;
+    unsafe {
+        core::arch::asm!("pushfq", "pop {}", out(reg) rflags, options(nomem, preserves_flags));
+        core::arch::asm!("cli", options(nomem, nostack));
+    }
+
+    let result = f();
+
+    if rflags & RFLAGS_INTERRUPT_FLAG != 0 {
+        unsafe {
+            core::arch::asm!("sti", options(nomem, nostack));
+        }
+    }
+
+    result
+}
@@ -0,0 +1,141 @@
+//! Funciones de control del sistema
+//!
+//! Operaciones que afectan a la máquina completa, como el reinicio o
+//! detener la CPU hasta la próxima interrupción.
+
+use crate::io::ports::write_port_u32;
+use crate::io::stdin::keyboard;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Comando del controlador 8042 que pulsa la línea de reset de la CPU
+const PULSE_RESET_LINE: u8 = 0xFE;
+
+/// Reinicia la máquina mediante el controlador de teclado
+///
+/// Usa el reset clásico del 8042: se vacía el buffer de salida, se espera
+/// a que el buffer de entrada esté libre y se envía el comando 0xFE al
+/// puerto 0x64, que pulsa la línea de reset de la CPU.
+///
+/// No todos los firmwares implementan este mecanismo (algunos equipos sin
+/// controlador PS/2 real lo ignoran). Si el reset no ocurre, se fuerza un
+/// triple fault, que la CPU resuelve reiniciándose.
+pub fn reboot() -> ! {
+    keyboard::flush_output_buffer();
+    // Si el controlador no responde queda el triple fault de abajo
+    let _ = keyboard::send_command(PULSE_RESET_LINE);
+
+    // Dar tiempo al controlador antes de recurrir al triple fault
+    for _ in 0..100000 {
+        core::hint::spin_loop();
+    }
+    triple_fault()
+}
+
+/// Provoca un triple fault cargando una IDT vacía y generando una excepción
+///
+/// Sin IDT válida, la excepción no puede atenderse, tampoco el double
+/// fault resultante, y la CPU se reinicia. En QEMU con `-no-reboot` la
+/// máquina virtual termina, lo que sirve para cerrar corridas automáticas.
+pub fn triple_fault() -> ! {
+    /// Puntero de IDT con límite 0: ningún vector es válido
+    #[repr(C, packed)]
+    struct IdtPointer {
+        limit: u16,
+        base: u64,
+    }
+
+    let null_idt = IdtPointer { limit: 0, base: 0 };
+    unsafe {
+        core::arch::asm!(
+            "lidt [{}]",
+            "int3",
+            in(reg) &null_idt,
+            options(readonly, nostack)
+        );
+    }
+
+    // No debería alcanzarse, pero por las dudas detener la CPU
+    loop {
+        hlt();
+    }
+}
+
+/// Qué hace el panic handler después de mostrar el mensaje
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PanicAction {
+    /// Detener la CPU y dejar el mensaje en pantalla
+    Halt,
+    /// Reiniciar la máquina con `reboot`
+    Reboot,
+    /// Forzar un triple fault (termina QEMU con `-no-reboot`)
+    TripleFault,
+}
+
+static PANIC_ACTION: AtomicU8 = AtomicU8::new(PanicAction::Halt as u8);
+
+/// Cambia qué hace el panic handler después de mostrar el mensaje
+///
+/// Por defecto es `PanicAction::Halt`.
+pub fn set_panic_action(action: PanicAction) {
+    PANIC_ACTION.store(action as u8, Ordering::Relaxed);
+}
+
+/// Acción configurada para los panics
+pub fn panic_action() -> PanicAction {
+    match PANIC_ACTION.load(Ordering::Relaxed) {
+        value if value == PanicAction::Reboot as u8 => PanicAction::Reboot,
+        value if value == PanicAction::TripleFault as u8 => PanicAction::TripleFault,
+        _ => PanicAction::Halt,
+    }
+}
+
+/// Detiene la CPU hasta la próxima interrupción (`hlt`)
+///
+/// No cambia el estado de las interrupciones: si están deshabilitadas, la
+/// CPU queda detenida hasta un NMI o un reset.
+#[inline]
+pub fn hlt() {
+    unsafe {
+        core::arch::asm!("hlt", options(nomem, nostack, preserves_flags));
+    }
+}
+
+/// Habilita las interrupciones y espera la próxima
+///
+/// `sti; hlt` se ejecuta como una secuencia: `sti` recién tiene efecto
+/// después de la instrucción siguiente, así que una interrupción que llega
+/// entre las dos no se pierde y despierta al `hlt`.
+///
+/// Solo es seguro una vez configurados la IDT y el PIC; el kernel todavía
+/// funciona por polling sin IDT, así que el loop principal
+/// (`event::EventLoop`) sigue esperando en un spin loop en lugar de usar
+/// esta función. Con interrupciones habilitadas y sin IDT, la primera IRQ
+/// causaría un triple fault.
+#[inline]
+#[expect(dead_code, reason = "sin IDT todavía no hay dónde usarla")]
+pub fn wait_for_interrupt() {
+    unsafe {
+        core::arch::asm!("sti", "hlt", options(nomem, nostack));
+    }
+}
+
+/// Puerto del dispositivo isa-debug-exit de QEMU
+const QEMU_DEBUG_EXIT_PORT: u16 = 0xF4;
+
+/// Termina QEMU con un código de salida
+///
+/// Requiere lanzar QEMU con
+/// `-device isa-debug-exit,iobase=0xf4,iosize=0x04`. QEMU termina con el
+/// estado `(code << 1) | 1`, así que un harness de pruebas debe traducirlo
+/// (por ejemplo, `code = 0x10` sale con 33). Fuera de QEMU o sin el
+/// dispositivo la escritura se ignora y la CPU queda detenida.
+pub fn qemu_exit(code: u32) -> ! {
+    unsafe {
+        write_port_u32(QEMU_DEBUG_EXIT_PORT, code);
+    }
+
+    loop {
+        hlt();
+    }
+}
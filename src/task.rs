@@ -0,0 +1,57 @@
+//! Ejecutor cooperativo de tareas
+//!
+//! Permite registrar funciones "tick" que el loop principal ejecuta una vez
+//! por iteración, después de procesar la entrada. No hay preemption ni
+//! hilos: cada tarea debe hacer un poco de trabajo y retornar rápido, sin
+//! bloquear, para no frenar al resto.
+
+use crate::sync::SpinLock;
+
+/// Cantidad máxima de tareas registradas
+pub const MAX_TASKS: usize = 8;
+
+/// Lugares para tareas; los vacíos quedan en `None`
+type TaskSlots = [Option<fn()>; MAX_TASKS];
+
+/// Tareas registradas, en orden de registro
+static TASKS: SpinLock<TaskSlots> = SpinLock::new([None; MAX_TASKS]);
+
+/// Registra una tarea para ejecutarse en cada iteración del loop principal
+///
+/// # Retorna
+/// `false` si ya hay `MAX_TASKS` tareas registradas
+pub fn register(task: fn()) -> bool {
+    let mut tasks = TASKS.lock();
+
+    match tasks.iter_mut().find(|slot| slot.is_none()) {
+        Some(slot) => {
+            *slot = Some(task);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Quita una tarea registrada previamente
+pub fn unregister(task: fn()) {
+    let mut tasks = TASKS.lock();
+
+    for slot in tasks.iter_mut() {
+        if slot.is_some_and(|registered| core::ptr::fn_addr_eq(registered, task)) {
+            *slot = None;
+        }
+    }
+}
+
+/// Ejecuta una vez cada tarea registrada
+///
+/// Las tareas se copian antes de ejecutarse, así que una tarea puede
+/// registrar o quitar tareas sin bloquear el ejecutor; los cambios se
+/// aplican en la siguiente iteración.
+pub fn run_tasks() {
+    let tasks = *TASKS.lock();
+
+    for task in tasks.iter().flatten() {
+        task();
+    }
+}
@@ -0,0 +1,177 @@
+//! Temporizador basado en el PIT (Intel 8253/8254)
+//!
+//! El canal 0 del PIT se programa como divisor continuo y se lee por
+//! polling: cada consulta mide cuántos pulsos pasaron desde la anterior y
+//! los acumula. No depende de interrupciones, pero el contador da una
+//! vuelta completa cada ~55 ms, así que `ticks()` debe consultarse al
+//! menos con esa frecuencia para no perder tiempo (el loop principal y
+//! `sleep_ms` lo hacen).
+
+use crate::io::ports::{read_port, write_port};
+use crate::sync::{SpinLock, without_interrupts};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Frecuencia de entrada del PIT en Hz
+pub const PIT_FREQUENCY: u64 = 1_193_182;
+
+/// Ticks por segundo reportados por `ticks()` (un tick = 1 ms)
+pub const TICKS_PER_SECOND: u64 = 1000;
+
+/// Puertos del PIT
+mod ports {
+    pub const CHANNEL_0: u16 = 0x40; // Contador del canal 0
+    pub const COMMAND: u16 = 0x43; // Registro de modo/comando
+}
+
+/// Comandos del PIT
+mod commands {
+    /// Canal 0, acceso byte bajo/alto, modo 2 (rate generator), binario
+    pub const CHANNEL_0_RATE_GENERATOR: u8 = 0x34;
+    /// Congela el valor actual del canal 0 para leerlo
+    pub const CHANNEL_0_LATCH: u8 = 0x00;
+}
+
+/// Estado acumulado del temporizador
+struct TimerState {
+    /// Último valor leído del contador (cuenta hacia abajo)
+    last_count: u16,
+    /// Pulsos del PIT acumulados desde `init`
+    elapsed_counts: u64,
+}
+
+static TIMER: SpinLock<TimerState> = SpinLock::new(TimerState {
+    last_count: 0,
+    elapsed_counts: 0,
+});
+
+/// Indica si `init` ya programó el PIT
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Lee el valor actual del contador del canal 0
+fn read_count() -> u16 {
+    unsafe {
+        write_port(ports::COMMAND, commands::CHANNEL_0_LATCH);
+        let low = read_port(ports::CHANNEL_0);
+        let high = read_port(ports::CHANNEL_0);
+        u16::from_le_bytes([low, high])
+    }
+}
+
+/// Programa el canal 0 del PIT y reinicia el contador de ticks
+///
+/// Usa un divisor de 65536 (valor de recarga 0), de modo que el contador
+/// recorre todo el rango `u16` y la diferencia entre dos lecturas se
+/// calcula con aritmética `wrapping`.
+pub fn init() {
+    without_interrupts(|| {
+        unsafe {
+            write_port(ports::COMMAND, commands::CHANNEL_0_RATE_GENERATOR);
+            write_port(ports::CHANNEL_0, 0x00);
+            write_port(ports::CHANNEL_0, 0x00);
+        }
+
+        let mut timer = TIMER.lock();
+        timer.last_count = read_count();
+        timer.elapsed_counts = 0;
+    });
+    RUNNING.store(true, Ordering::Release);
+}
+
+/// Indica si el temporizador está funcionando
+///
+/// Hasta que se llama a `init`, `ticks()` no mide tiempo real y no sirve
+/// para calcular plazos.
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::Acquire)
+}
+
+/// Milisegundos transcurridos desde `init`
+pub fn ticks() -> u64 {
+    without_interrupts(|| {
+        let mut timer = TIMER.lock();
+        let count = read_count();
+
+        // El contador decrece, así que lo transcurrido es anterior - actual
+        timer.elapsed_counts += timer.last_count.wrapping_sub(count) as u64;
+        timer.last_count = count;
+
+        timer.elapsed_counts * TICKS_PER_SECOND / PIT_FREQUENCY
+    })
+}
+
+/// Espera activamente la cantidad de milisegundos indicada
+pub fn sleep_ms(ms: u64) {
+    let start = ticks();
+    while ticks().wrapping_sub(start) < ms {
+        core::hint::spin_loop();
+    }
+}
+
+/// Mantiene un loop a intervalos fijos
+///
+/// ```ignore
+/// let mut throttle = Throttle::new(16);
+/// loop {
+///     // trabajo del cuadro
+///     throttle.wait_until_next_frame();
+/// }
+/// ```
+pub struct Throttle {
+    /// Duración de cada cuadro en milisegundos
+    interval_ms: u64,
+    /// Tick en el que empezó el cuadro actual
+    frame_start: u64,
+}
+
+impl Throttle {
+    /// Crea un throttle cuyo primer cuadro empieza ahora
+    pub fn new(interval_ms: u64) -> Self {
+        Self {
+            interval_ms,
+            frame_start: ticks(),
+        }
+    }
+
+    /// Espera lo que falta del cuadro actual y empieza el siguiente
+    ///
+    /// El siguiente cuadro empieza exactamente un intervalo después del
+    /// anterior, así que las variaciones del trabajo de cada cuadro no se
+    /// acumulan. Si el cuadro tardó más que el intervalo no se espera, y
+    /// el siguiente cuadro empieza ahora en lugar de intentar recuperar los
+    /// cuadros perdidos.
+    pub fn wait_until_next_frame(&mut self) {
+        let next = self.frame_start + self.interval_ms;
+        let now = ticks();
+
+        if now >= next {
+            self.frame_start = now;
+            return;
+        }
+
+        sleep_ms(next - now);
+        self.frame_start = next;
+    }
+
+    /// Versión sin espera de `wait_until_next_frame`
+    ///
+    /// Si el cuadro actual terminó, empieza el siguiente y retorna `true`;
+    /// si no, retorna `false` sin esperar. Pensada para loops que atienden
+    /// otras cosas mientras tanto. Igual que `wait_until_next_frame`, un
+    /// atraso de más de un intervalo no se recupera: el siguiente cuadro
+    /// empieza ahora.
+    pub fn poll_frame(&mut self) -> bool {
+        let next = self.frame_start + self.interval_ms;
+        let now = ticks();
+
+        if now < next {
+            return false;
+        }
+
+        self.frame_start = if now - next >= self.interval_ms {
+            now
+        } else {
+            next
+        };
+        true
+    }
+}